@@ -0,0 +1,442 @@
+//! Headless trainer, e.g. for running long experiments on CI:
+//!
+//! ```text
+//! flyer-cli train --generations 100 --seed 42 --config config.json --out run
+//! flyer-cli resume run/snapshot.bin --generations 100 --out run
+//! flyer-cli eval run/genome.json --config config.json --runs 10
+//! ```
+//!
+//! Each run writes into its output directory:
+//!
+//! - `snapshot.bin` - the entire simulation, for `resume`,
+//! - `history.csv` - statistics of all the generations evolved so far
+//!   (`resume` appends to it, since snapshots keep only the latest ones),
+//! - `genome.json` - eye and brain of the fittest animal of the last
+//!   generation, for `eval`.
+
+use anyhow::{Context, Result};
+use clap::{Args, Parser, Subcommand};
+use lib_genetic_algorithm as ga;
+use lib_simulation as sim;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::fs::{self, OpenOptions};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Parser)]
+#[command(version, about = "Trains and evaluates birds without a browser")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Starts a new training run
+    Train {
+        #[command(flatten)]
+        world: WorldArgs,
+
+        #[command(flatten)]
+        run: RunArgs,
+    },
+
+    /// Continues a training run from its snapshot
+    Resume {
+        /// Snapshot to resume; JSON if it ends with `.json`, binary otherwise
+        snapshot: PathBuf,
+
+        #[command(flatten)]
+        run: RunArgs,
+    },
+
+    /// Runs generations where all the animals share the given genome and
+    /// prints their statistics, without evolving them
+    Eval {
+        genome: PathBuf,
+
+        #[command(flatten)]
+        world: WorldArgs,
+
+        /// Number of generations to evaluate, each with a different seed
+        #[arg(long, default_value_t = 1)]
+        runs: u64,
+    },
+}
+
+#[derive(Debug, Args)]
+struct WorldArgs {
+    /// JSON file with simulation's config; missing fields keep their default
+    /// values
+    #[arg(long)]
+    config: Option<PathBuf>,
+
+    #[arg(long, default_value_t = 0)]
+    seed: u64,
+}
+
+#[derive(Debug, Args)]
+struct RunArgs {
+    #[arg(long)]
+    generations: usize,
+
+    /// Directory to write the snapshot, history and best genome into
+    #[arg(long, default_value = ".")]
+    out: PathBuf,
+
+    /// Writes the outputs also every this many generations, not only at the
+    /// end
+    #[arg(long)]
+    checkpoint_every: Option<usize>,
+}
+
+fn main() -> Result<()> {
+    run(Cli::parse())
+}
+
+fn run(cli: Cli) -> Result<()> {
+    match cli.command {
+        Command::Train { world, run } => {
+            let config = load_config(world.config.as_deref())?;
+            let (sim, rng) = sim::Simulation::from_seed(config, world.seed);
+
+            train(sim, rng, &run, false)
+        }
+
+        Command::Resume { snapshot, run } => {
+            let (sim, rng) = sim::Simulation::restore(load_snapshot(&snapshot)?)?;
+
+            train(sim, rng, &run, true)
+        }
+
+        Command::Eval {
+            genome,
+            world,
+            runs,
+        } => {
+            let config = load_config(world.config.as_deref())?;
+
+            let genome = fs::read_to_string(&genome)
+                .with_context(|| format!("couldn't read {}", genome.display()))?;
+
+            let genome = sim::Genome::from_json(&config, &genome)?;
+
+            for run in 0..runs {
+                let mut rng = ChaCha8Rng::seed_from_u64(world.seed.wrapping_add(run));
+                let mut sim = sim::Simulation::from_genome(config.clone(), &genome, &mut rng);
+
+                println!("run {}: {}", run, summary(&sim.evaluate(&mut rng)));
+            }
+
+            Ok(())
+        }
+    }
+}
+
+fn train(
+    mut sim: sim::Simulation,
+    mut rng: ChaCha8Rng,
+    args: &RunArgs,
+    resumed: bool,
+) -> Result<()> {
+    fs::create_dir_all(&args.out)
+        .with_context(|| format!("couldn't create {}", args.out.display()))?;
+
+    let history = args.out.join("history.csv");
+    let fresh = !resumed || !history.exists();
+
+    if fresh {
+        let mut csv = Vec::new();
+
+        ga::History::write_csv_header(&mut csv)?;
+        write(&history, &csv)?;
+    }
+
+    // Restored simulation carries the latest generation's statistics, which
+    // are already in the CSV - unless it's just been created
+    let mut saved = if fresh { 0 } else { sim.history().len() };
+
+    for generation in 1..=args.generations {
+        let stats = sim.train(&mut rng);
+
+        println!("generation {}: {}", stats.generation, summary(&stats));
+
+        let checkpoint = args
+            .checkpoint_every
+            .is_some_and(|every| every > 0 && generation.is_multiple_of(every));
+
+        if checkpoint && generation < args.generations {
+            save(&sim, &rng, &args.out, &mut saved)?;
+        }
+    }
+
+    save(&sim, &rng, &args.out, &mut saved)
+}
+
+fn summary(stats: &ga::Statistics) -> String {
+    format!(
+        "min={:.2}, max={:.2}, avg={:.2}, median={:.2}, std_dev={:.2}, distance={:.4}, species={}",
+        stats.min_fitness,
+        stats.max_fitness,
+        stats.avg_fitness,
+        stats.median_fitness,
+        stats.std_dev_fitness,
+        stats.mean_distance,
+        stats.species_sizes.len(),
+    )
+}
+
+fn load_config(path: Option<&Path>) -> Result<sim::Config> {
+    let Some(path) = path else {
+        return Ok(sim::Config::default());
+    };
+
+    let json =
+        fs::read_to_string(path).with_context(|| format!("couldn't read {}", path.display()))?;
+
+    let config: sim::Config = serde_json::from_str(&json)
+        .with_context(|| format!("couldn't parse {}", path.display()))?;
+
+    config
+        .validate()
+        .with_context(|| format!("invalid config in {}", path.display()))?;
+
+    Ok(config)
+}
+
+fn load_snapshot(path: &Path) -> Result<sim::Snapshot> {
+    let snapshot = if is_json(path) {
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("couldn't read {}", path.display()))?;
+
+        sim::Snapshot::from_json(&json)
+    } else {
+        let bytes = fs::read(path).with_context(|| format!("couldn't read {}", path.display()))?;
+
+        sim::Snapshot::from_bytes(&bytes)
+    };
+
+    snapshot.with_context(|| format!("couldn't load {}", path.display()))
+}
+
+/// Writes the snapshot and best genome, and appends statistics of
+/// generations evolved since the previous save (`saved` of them have been
+/// written so far) to the history.
+fn save(sim: &sim::Simulation, rng: &ChaCha8Rng, out: &Path, saved: &mut usize) -> Result<()> {
+    write(&out.join("snapshot.bin"), &sim.snapshot(rng).to_bytes()?)?;
+
+    let path = out.join("history.csv");
+
+    let file = OpenOptions::new()
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("couldn't open {}", path.display()))?;
+
+    let mut writer = BufWriter::new(file);
+
+    sim.history()
+        .iter()
+        .skip(*saved)
+        .try_for_each(|stats| stats.write_csv(&mut writer))
+        .and_then(|()| writer.flush())
+        .with_context(|| format!("couldn't write {}", path.display()))?;
+
+    *saved = sim.history().len();
+
+    if let Some(genome) = sim.best_genome() {
+        write(&out.join("genome.json"), genome.to_json()?.as_bytes())?;
+    }
+
+    Ok(())
+}
+
+fn write(path: &Path, contents: &[u8]) -> Result<()> {
+    fs::write(path, contents).with_context(|| format!("couldn't write {}", path.display()))
+}
+
+fn is_json(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "json")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    fn cli(args: &[&str]) -> Cli {
+        Cli::try_parse_from([&["flyer-cli"], args].concat()).unwrap()
+    }
+
+    fn path(path: &Path) -> &str {
+        path.to_str().unwrap()
+    }
+
+    #[test]
+    fn verify_cli() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn train_resume_eval() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.json");
+        let out = dir.path().join("run");
+
+        fs::write(
+            &config,
+            r#"{ "sim_generation_length": 20, "world_animals": 10, "eye_evolve": true }"#,
+        )
+        .unwrap();
+
+        run(cli(&[
+            "train",
+            "--generations",
+            "2",
+            "--seed",
+            "42",
+            "--config",
+            path(&config),
+            "--out",
+            path(&out),
+        ]))
+        .unwrap();
+
+        let snapshot = out.join("snapshot.bin");
+        let history = fs::read_to_string(out.join("history.csv")).unwrap();
+
+        assert_eq!(history.lines().count(), 3);
+
+        run(cli(&[
+            "resume",
+            path(&snapshot),
+            "--generations",
+            "1",
+            "--out",
+            path(&out),
+        ]))
+        .unwrap();
+
+        let (sim, _) = sim::Simulation::restore(load_snapshot(&snapshot).unwrap()).unwrap();
+        let history = fs::read_to_string(out.join("history.csv")).unwrap();
+
+        assert_eq!(sim.config().world_animals, 10);
+        assert_eq!(sim.generation(), 3);
+
+        // Resumed history matches the one of an uninterrupted run
+        let uninterrupted = dir.path().join("uninterrupted");
+
+        run(cli(&[
+            "train",
+            "--generations",
+            "3",
+            "--seed",
+            "42",
+            "--config",
+            path(&config),
+            "--out",
+            path(&uninterrupted),
+        ]))
+        .unwrap();
+
+        assert_eq!(history.lines().count(), 4);
+        assert_eq!(
+            history,
+            fs::read_to_string(uninterrupted.join("history.csv")).unwrap()
+        );
+
+        // Exported genome keeps the evolved eye
+        let genome = fs::read_to_string(out.join("genome.json")).unwrap();
+
+        assert_eq!(
+            sim::Genome::from_json(sim.config(), &genome).unwrap(),
+            sim.best_genome().unwrap()
+        );
+
+        run(cli(&[
+            "eval",
+            path(&out.join("genome.json")),
+            "--config",
+            path(&config),
+            "--runs",
+            "2",
+        ]))
+        .unwrap();
+
+        // Genome doesn't fit eyes with a different number of cells
+        fs::write(&config, r#"{ "eye_cells": 3 }"#).unwrap();
+
+        assert!(
+            run(cli(&[
+                "eval",
+                path(&out.join("genome.json")),
+                "--config",
+                path(&config),
+            ]))
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn resume_into_fresh_directory() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.json");
+        let out = dir.path().join("run");
+        let resumed = dir.path().join("resumed");
+
+        fs::write(
+            &config,
+            r#"{ "sim_generation_length": 20, "world_animals": 10 }"#,
+        )
+        .unwrap();
+
+        run(cli(&[
+            "train",
+            "--generations",
+            "2",
+            "--config",
+            path(&config),
+            "--out",
+            path(&out),
+        ]))
+        .unwrap();
+
+        run(cli(&[
+            "resume",
+            path(&out.join("snapshot.bin")),
+            "--generations",
+            "1",
+            "--out",
+            path(&resumed),
+        ]))
+        .unwrap();
+
+        // Header, the restored latest generation and the resumed one
+        let history = fs::read_to_string(resumed.join("history.csv")).unwrap();
+        let original = fs::read_to_string(out.join("history.csv")).unwrap();
+
+        assert_eq!(history.lines().count(), 3);
+        assert_eq!(history.lines().nth(1), original.lines().nth(2));
+    }
+
+    #[test]
+    fn rejects_invalid_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("config.json");
+
+        fs::write(&config, r#"{ "ga_mut_chance": 2.0 }"#).unwrap();
+
+        let err = run(cli(&[
+            "train",
+            "--generations",
+            "1",
+            "--config",
+            path(&config),
+            "--out",
+            path(&dir.path().join("run")),
+        ]))
+        .unwrap_err();
+
+        assert!(format!("{err:#}").contains("ga_mut_chance"));
+    }
+}
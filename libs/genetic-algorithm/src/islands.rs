@@ -0,0 +1,337 @@
+use crate::*;
+use std::borrow::BorrowMut;
+
+/// Which islands exchange migrants with which.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Topology {
+    /// Island `n` sends its migrants to island `n + 1`, the last one to the
+    /// first one
+    #[default]
+    Ring,
+
+    /// Each island receives the fittest migrants among all the other
+    /// islands
+    FullyConnected,
+}
+
+/// Periodically copies the fittest individuals of each island into its
+/// neighbours, so that islands evolve mostly independently, but good genes
+/// still get to spread.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Migration {
+    topology: Topology,
+
+    /// Migration happens after every `interval`-th generation
+    interval: usize,
+
+    /// Number of migrants received by each island
+    count: usize,
+}
+
+impl Migration {
+    pub fn new(topology: Topology, interval: usize, count: usize) -> Self {
+        assert!(interval > 0);
+
+        Self {
+            topology,
+            interval,
+            count,
+        }
+    }
+
+    /// Performs migration after the `generation`-th generation (counting
+    /// from zero) got evolved, if it's due.
+    ///
+    /// Migrants are picked from `evaluated` populations (the ones whose
+    /// fitness is known) and replace the last individuals of `evolved`
+    /// ones, i.e. offspring rather than survivors.
+    pub fn migrate<I>(&self, generation: usize, evaluated: &[Vec<I>], evolved: &mut [Vec<I>])
+    where
+        I: Individual,
+    {
+        assert_eq!(evaluated.len(), evolved.len());
+
+        let islands = evaluated.len();
+
+        if islands < 2 || self.count == 0 || !(generation + 1).is_multiple_of(self.interval) {
+            return;
+        }
+
+        for (dst, population) in evolved.iter_mut().enumerate() {
+            let sources: Vec<_> = match self.topology {
+                Topology::Ring => vec![(dst + islands - 1) % islands],
+                Topology::FullyConnected => (0..islands).filter(|&src| src != dst).collect(),
+            };
+
+            let mut candidates: Vec<_> = sources
+                .into_iter()
+                .flat_map(|src| &evaluated[src])
+                .collect();
+
+            // Fittest first; stable, so that ties are broken by island and
+            // position within it
+            candidates.sort_by(|a, b| b.fitness().total_cmp(&a.fitness()));
+
+            let count = self.count.min(candidates.len()).min(population.len());
+            let offset = population.len() - count;
+
+            for (slot, migrant) in population[offset..].iter_mut().zip(candidates) {
+                *slot = I::create(migrant.chromosome().clone());
+            }
+        }
+    }
+}
+
+/// Several populations ("islands"), each evolved by its own genetic
+/// algorithm, with occasional [`Migration`] between them.
+///
+/// Islands can differ in everything but the type of their selection method,
+/// e.g. use different mutation rates or replacement strategies.
+///
+/// Algorithms can be either owned or borrowed (`G` is anything that
+/// [`BorrowMut`]s a [`GeneticAlgorithm`]), e.g. from simulations that keep
+/// each island's algorithm next to its world.
+pub struct IslandModel<G> {
+    islands: Vec<G>,
+    migration: Migration,
+}
+
+impl<G> IslandModel<G> {
+    pub fn new(islands: Vec<G>, migration: Migration) -> Self {
+        assert!(!islands.is_empty());
+
+        Self { islands, migration }
+    }
+
+    pub fn islands(&self) -> &[G] {
+        &self.islands
+    }
+
+    /// Evolves each of the `populations` (one per island, in order) and then
+    /// migrates individuals between them.
+    pub fn evolve<S, I>(
+        &mut self,
+        rng: &mut dyn RngCore,
+        populations: &[Vec<I>],
+    ) -> (Vec<Vec<I>>, Vec<Statistics>)
+    where
+        G: BorrowMut<GeneticAlgorithm<S>>,
+        S: SelectionMethod,
+        I: Individual,
+    {
+        self.evolve_with(populations, |islands, populations| {
+            islands
+                .iter_mut()
+                .zip(populations)
+                .map(|(ga, population)| ga.borrow_mut().evolve(rng, population))
+                .collect()
+        })
+    }
+
+    /// Like [`IslandModel::evolve()`], but leaves evolving the islands up to
+    /// `evolve`, which gets all the algorithms together with their
+    /// populations and returns what each of them produced, in order - e.g. so
+    /// that each island can draw from a generator of its own or be evolved on
+    /// a separate thread.
+    pub fn evolve_with<S, I, F>(
+        &mut self,
+        populations: &[Vec<I>],
+        evolve: F,
+    ) -> (Vec<Vec<I>>, Vec<Statistics>)
+    where
+        G: BorrowMut<GeneticAlgorithm<S>>,
+        S: SelectionMethod,
+        I: Individual,
+        F: FnOnce(&mut [G], &[Vec<I>]) -> Vec<(Vec<I>, Statistics)>,
+    {
+        assert_eq!(populations.len(), self.islands.len());
+
+        let generation = self.islands[0].borrow().generation();
+
+        let (mut evolved, stats): (Vec<_>, Vec<_>) =
+            evolve(&mut self.islands, populations).into_iter().unzip();
+
+        assert_eq!(evolved.len(), populations.len());
+
+        self.migration
+            .migrate(generation, populations, &mut evolved);
+
+        (evolved, stats)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[derive(Debug, PartialEq)]
+    struct TestIndividual {
+        chromosome: Chromosome,
+    }
+
+    impl Candidate for TestIndividual {
+        fn chromosome(&self) -> &Chromosome {
+            &self.chromosome
+        }
+
+        fn fitness(&self) -> f32 {
+            self.chromosome[0]
+        }
+    }
+
+    impl Individual for TestIndividual {
+        fn create(chromosome: Chromosome) -> Self {
+            Self { chromosome }
+        }
+    }
+
+    fn individual(fitness: f32) -> TestIndividual {
+        TestIndividual::create([fitness].into_iter().collect())
+    }
+
+    fn islands(fitnesses: &[&[f32]]) -> Vec<Vec<TestIndividual>> {
+        fitnesses
+            .iter()
+            .map(|island| island.iter().copied().map(individual).collect())
+            .collect()
+    }
+
+    fn fitnesses(islands: &[Vec<TestIndividual>]) -> Vec<Vec<f32>> {
+        islands
+            .iter()
+            .map(|island| {
+                island
+                    .iter()
+                    .map(|individual| individual.fitness())
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn evaluated() -> Vec<Vec<TestIndividual>> {
+        islands(&[
+            &[1.0, 3.0, 2.0],
+            &[10.0, 30.0, 20.0],
+            &[100.0, 300.0, 200.0],
+        ])
+    }
+
+    fn evolved() -> Vec<Vec<TestIndividual>> {
+        islands(&[&[0.0, 0.0, 0.0], &[0.0, 0.0, 0.0], &[0.0, 0.0, 0.0]])
+    }
+
+    #[test]
+    fn ring_migration() {
+        let mut evolved = evolved();
+
+        Migration::new(Topology::Ring, 1, 2).migrate(0, &evaluated(), &mut evolved);
+
+        assert_eq!(
+            fitnesses(&evolved),
+            [[0.0, 300.0, 200.0], [0.0, 3.0, 2.0], [0.0, 30.0, 20.0]]
+        );
+    }
+
+    #[test]
+    fn fully_connected_migration() {
+        let mut evolved = evolved();
+
+        Migration::new(Topology::FullyConnected, 1, 2).migrate(0, &evaluated(), &mut evolved);
+
+        assert_eq!(
+            fitnesses(&evolved),
+            [[0.0, 300.0, 200.0], [0.0, 300.0, 200.0], [0.0, 30.0, 20.0]]
+        );
+    }
+
+    #[test]
+    fn migrates_every_interval() {
+        let migration = Migration::new(Topology::Ring, 3, 1);
+
+        for generation in 0..6 {
+            let mut evolved = evolved();
+
+            migration.migrate(generation, &evaluated(), &mut evolved);
+
+            let migrated = evolved != self::evolved();
+
+            assert_eq!(migrated, generation == 2 || generation == 5);
+        }
+    }
+
+    #[test]
+    fn island_model() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        let ga = || {
+            GeneticAlgorithm::new(
+                RouletteWheelSelection,
+                UniformCrossover,
+                GaussianMutation::new(0.0, 0.0),
+            )
+        };
+
+        let mut model = IslandModel::new(
+            vec![ga(), ga().with_elitism(1)],
+            Migration::new(Topology::Ring, 1, 1),
+        );
+
+        let populations = islands(&[&[1.0, 2.0, 3.0], &[10.0, 20.0, 30.0]]);
+        let (evolved, stats) = model.evolve(&mut rng, &populations);
+
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[1].survivors, 1);
+
+        // The elite of the second island and migrants are known exactly
+        assert_eq!(evolved[1][0], individual(30.0));
+        assert_eq!(evolved[1][2], individual(3.0));
+        assert_eq!(evolved[0][2], individual(30.0));
+
+        assert!(model.islands().iter().all(|ga| ga.generation() == 1));
+    }
+    #[test]
+    fn island_model_with_borrowed_algorithms() {
+        let ga = || {
+            GeneticAlgorithm::new(
+                RouletteWheelSelection,
+                UniformCrossover,
+                GaussianMutation::new(0.5, 0.5),
+            )
+        };
+
+        let populations = islands(&[&[1.0, 2.0, 3.0], &[10.0, 20.0, 30.0]]);
+        let migration = Migration::new(Topology::Ring, 1, 1);
+
+        // Each island with a generator of its own ...
+        let mut gas = [ga(), ga()];
+        let mut rngs = [0, 1].map(ChaCha8Rng::seed_from_u64);
+
+        let (evolved, stats) = IslandModel::new(gas.iter_mut().collect(), migration.clone())
+            .evolve_with(&populations, |gas, populations| {
+                gas.iter_mut()
+                    .zip(&mut rngs)
+                    .zip(populations)
+                    .map(|((ga, rng), population)| ga.evolve(rng, population))
+                    .collect()
+            });
+
+        // ... is the same as evolving them separately and migrating after
+        let mut rngs = [0, 1].map(ChaCha8Rng::seed_from_u64);
+
+        let (mut expected, expected_stats): (Vec<_>, Vec<_>) = [ga(), ga()]
+            .iter_mut()
+            .zip(&mut rngs)
+            .zip(&populations)
+            .map(|((ga, rng), population)| ga.evolve(rng, population))
+            .unzip();
+
+        migration.migrate(0, &populations, &mut expected);
+
+        assert_eq!(evolved, expected);
+        assert_eq!(stats, expected_stats);
+        assert!(gas.iter().all(|ga| ga.generation() == 1));
+    }
+}
@@ -1,11 +1,55 @@
+mod islands;
+mod speciation;
+mod statistics;
+
+pub use self::{islands::*, speciation::*, statistics::*};
+use rand::distr::weighted::Error as WeightError;
 use rand::seq::{IndexedRandom, SliceRandom};
 use rand::{Rng, RngCore};
-use std::ops::Index;
+use serde::{Deserialize, Serialize};
+use std::ops::{Index, RangeInclusive};
 
 pub struct GeneticAlgorithm<S> {
     selection_method: S,
-    crossover_method: Box<dyn CrossoverMethod>,
-    mutation_method: Box<dyn MutationMethod>,
+    // `Send + Sync`, so that independent populations can be evolved on
+    // separate threads
+    crossover_method: Box<dyn CrossoverMethod + Send + Sync>,
+    mutation_method: Box<dyn MutationMethod + Send + Sync>,
+
+    /// Number of the fittest individuals copied unchanged into the next
+    /// generation
+    elitism: usize,
+    replacement: Replacement,
+    speciation: Option<Speciation>,
+
+    /// Number of `evolve()`s performed so far
+    generation: usize,
+}
+
+/// Decides which individuals make it into the next generation.
+///
+/// Fitness of the offspring is not known until they are evaluated, so all
+/// the strategies rank the *current* population only - e.g. (μ+λ) keeps the
+/// best μ of parents and their (already evaluated) children from the
+/// previous generation.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Replacement {
+    /// The entire population is replaced by offspring
+    #[default]
+    Generational,
+
+    /// Only the `count` least fit individuals are replaced by offspring,
+    /// everybody else survives unchanged
+    SteadyState { count: usize },
+
+    /// The `mu` fittest individuals survive and are the only ones allowed
+    /// to breed; offspring fill up the rest of the population
+    MuPlusLambda { mu: usize },
+
+    /// The `mu` fittest individuals are the only ones allowed to breed, but
+    /// none of them survives - the entire population is replaced by their
+    /// offspring
+    MuCommaLambda { mu: usize },
 }
 
 impl<S> GeneticAlgorithm<S>
@@ -14,42 +58,228 @@ where
 {
     pub fn new(
         selection_method: S,
-        crossover_method: impl CrossoverMethod + 'static,
-        mutation_method: impl MutationMethod + 'static,
+        crossover_method: impl CrossoverMethod + Send + Sync + 'static,
+        mutation_method: impl MutationMethod + Send + Sync + 'static,
     ) -> Self {
         Self {
             selection_method,
             crossover_method: Box::new(crossover_method),
             mutation_method: Box::new(mutation_method),
+            elitism: 0,
+            replacement: Replacement::default(),
+            speciation: None,
+            generation: 0,
+        }
+    }
+
+    /// Copies `elitism` fittest individuals unchanged into each next
+    /// generation, on top of whatever survives due to [`Replacement`].
+    pub fn with_elitism(mut self, elitism: usize) -> Self {
+        self.elitism = elitism;
+        self
+    }
+
+    pub fn with_replacement(mut self, replacement: Replacement) -> Self {
+        match replacement {
+            Replacement::Generational => {}
+            Replacement::SteadyState { count } => assert!(count > 0),
+            Replacement::MuPlusLambda { mu } | Replacement::MuCommaLambda { mu } => {
+                assert!(mu > 0)
+            }
         }
+
+        self.replacement = replacement;
+        self
+    }
+
+    /// Makes selection work on fitness shared within species; see
+    /// [`Speciation`].
+    pub fn with_speciation(mut self, speciation: Speciation) -> Self {
+        self.speciation = Some(speciation);
+        self
+    }
+
+    pub fn speciation(&self) -> Option<&Speciation> {
+        self.speciation.as_ref()
+    }
+
+    pub fn speciation_mut(&mut self) -> Option<&mut Speciation> {
+        self.speciation.as_mut()
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Overrides the generation counter, e.g. when resuming an interrupted
+    /// run, so that schedules (see [`ScheduledMutation`]) pick up where they
+    /// left off.
+    pub fn set_generation(&mut self, generation: usize) {
+        self.generation = generation;
     }
 
-    pub fn evolve<I>(&self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
+    /// Describes `population` of the current generation without evolving
+    /// it, e.g. to evaluate a trained one; nothing survives and nothing gets
+    /// bred, so both `survivors` and `offspring` are zero.
+    pub fn evaluate<I>(&self, population: &[I]) -> Statistics
+    where
+        I: Candidate,
+    {
+        Statistics::new(self.generation, population, 0, 0)
+    }
+
+    pub fn evolve<I>(&mut self, rng: &mut dyn RngCore, population: &[I]) -> (Vec<I>, Statistics)
     where
         I: Individual,
     {
         assert!(!population.is_empty());
 
-        let new_population = (0..population.len())
-            .map(|_| {
-                let parent_a = self.selection_method.select(rng, population).chromosome();
-                let parent_b = self.selection_method.select(rng, population).chromosome();
+        self.mutation_method.set_generation(self.generation);
 
-                let mut child = self.crossover_method.crossover(rng, parent_a, parent_b);
+        let len = population.len();
 
-                self.mutation_method.mutate(rng, &mut child);
+        let (survivors, breeders) = match self.replacement {
+            Replacement::Generational => (0, len),
 
-                I::create(child)
-            })
+            Replacement::SteadyState { count } => {
+                assert!(
+                    count <= len,
+                    "steady-state replaces more than the population"
+                );
+
+                (len - count, len)
+            }
+
+            Replacement::MuPlusLambda { mu } => {
+                assert!(mu <= len, "mu is larger than the population");
+
+                (mu, mu)
+            }
+
+            Replacement::MuCommaLambda { mu } => {
+                assert!(mu <= len, "mu is larger than the population");
+
+                (0, mu)
+            }
+        };
+
+        let survivors = survivors.max(self.elitism).min(len);
+        let offspring = len - survivors;
+
+        // Indices of individuals, the fittest first; only needed when
+        // somebody is singled out, which keeps the plain generational
+        // replacement independent of population order
+        let ranked: Vec<_> = if survivors > 0 || breeders < len {
+            let mut ranked: Vec<_> = (0..len).collect();
+
+            ranked.sort_by(|&a, &b| population[b].fitness().total_cmp(&population[a].fitness()));
+            ranked
+        } else {
+            Vec::new()
+        };
+
+        let mut new_population: Vec<_> = ranked[..survivors]
+            .iter()
+            .map(|&idx| I::create(population[idx].chromosome().clone()))
             .collect();
 
-        let stats = Statistics::new(population);
+        let speciated = self
+            .speciation
+            .as_mut()
+            .map(|speciation| speciation.speciate(population));
+
+        let children = if breeders == len && speciated.is_none() {
+            self.breed(rng, population, offspring)
+        } else {
+            let pool = if breeders < len {
+                ranked[..breeders].to_vec()
+            } else {
+                (0..len).collect()
+            };
+
+            let breeders: Vec<_> = pool
+                .into_iter()
+                .map(|idx| Breeder {
+                    individual: &population[idx],
+                    fitness: speciated.as_ref().map_or_else(
+                        || population[idx].fitness(),
+                        |speciated| speciated.fitness[idx],
+                    ),
+                })
+                .collect();
+
+            self.breed(rng, &breeders, offspring)
+        };
+
+        new_population.extend(children.into_iter().map(I::create));
+
+        let mut stats = Statistics::new(self.generation, population, survivors, offspring);
+
+        if let Some(speciated) = speciated {
+            stats.species_sizes = speciated.species_sizes;
+        }
+
+        self.generation += 1;
 
         (new_population, stats)
     }
+
+    fn breed<I>(&self, rng: &mut dyn RngCore, parents: &[I], count: usize) -> Vec<Chromosome>
+    where
+        I: Candidate,
+    {
+        let child = |rng: &mut dyn RngCore, parent_a: &I, parent_b: &I| {
+            let mut child =
+                self.crossover_method
+                    .crossover(rng, parent_a.chromosome(), parent_b.chromosome());
+
+            self.mutation_method.mutate(rng, &mut child);
+
+            child
+        };
+
+        if self.selection_method.selects_all_at_once() {
+            let parents = self.selection_method.select_many(rng, parents, 2 * count);
+
+            parents
+                .chunks_exact(2)
+                .map(|parents| child(rng, parents[0], parents[1]))
+                .collect()
+        } else {
+            (0..count)
+                .map(|_| {
+                    let parents = self.selection_method.select_many(rng, parents, 2);
+
+                    child(rng, parents[0], parents[1])
+                })
+                .collect()
+        }
+    }
 }
 
-#[derive(Clone, Debug)]
+/// Borrowed individual, so that a subset of the population (possibly with
+/// adjusted fitness) can be handed to a [`SelectionMethod`] without cloning
+/// it.
+struct Breeder<'a, I> {
+    individual: &'a I,
+    fitness: f32,
+}
+
+impl<I> Candidate for Breeder<'_, I>
+where
+    I: Candidate,
+{
+    fn fitness(&self) -> f32 {
+        self.fitness
+    }
+
+    fn chromosome(&self) -> &Chromosome {
+        self.individual.chromosome()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct Chromosome {
     genes: Vec<f32>,
 }
@@ -59,6 +289,10 @@ impl Chromosome {
         self.genes.len()
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.genes.is_empty()
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &f32> {
         self.genes.iter()
     }
@@ -93,31 +327,294 @@ impl IntoIterator for Chromosome {
     }
 }
 
-pub trait Individual {
+/// Something [`SelectionMethod`]s can pick from - a chromosome together
+/// with its fitness.
+pub trait Candidate {
     fn fitness(&self) -> f32;
     fn chromosome(&self) -> &Chromosome;
+}
+
+/// Member of a population, which [`GeneticAlgorithm`] also creates out of
+/// freshly bred chromosomes.
+pub trait Individual: Candidate {
     fn create(chromosome: Chromosome) -> Self;
 }
 
 pub trait SelectionMethod {
     fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
     where
-        I: Individual;
+        I: Candidate;
+
+    /// Selects `count` individuals at once.
+    ///
+    /// By default this just calls `select()` repeatedly, but methods such as
+    /// [`StochasticUniversalSampling`] override it.
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Candidate,
+    {
+        (0..count).map(|_| self.select(rng, population)).collect()
+    }
+
+    /// Whether [`GeneticAlgorithm`] has to pick all the parents of the next
+    /// generation with a single `select_many()` call, e.g. because they're
+    /// spread over the population together.
+    ///
+    /// Otherwise parents are picked pair by pair, each pair followed by
+    /// crossover and mutation of their child.
+    fn selects_all_at_once(&self) -> bool {
+        false
+    }
 }
 
+/// Fitness-proportionate selection.
+///
+/// If all the individuals have zero fitness, each of them is equally likely
+/// to be chosen; negative, infinite or NaN fitness is a bug and panics.
 pub struct RouletteWheelSelection;
 
 impl SelectionMethod for RouletteWheelSelection {
     fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
     where
-        I: Individual,
+        I: Candidate,
+    {
+        assert!(!population.is_empty(), "got an empty population");
+
+        match population.choose_weighted(rng, |individual| individual.fitness()) {
+            Ok(individual) => individual,
+            Err(WeightError::InsufficientNonZero) => choose_uniformly(rng, population),
+            Err(err) => panic!("got invalid fitness: {err}"),
+        }
+    }
+}
+
+/// Picks `size` random individuals and returns the fittest one; the bigger
+/// the tournament, the higher the selection pressure.
+pub struct TournamentSelection {
+    size: usize,
+}
+
+impl TournamentSelection {
+    pub fn new(size: usize) -> Self {
+        assert!(size > 0);
+
+        Self { size }
+    }
+}
+
+impl SelectionMethod for TournamentSelection {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Candidate,
+    {
+        assert!(!population.is_empty(), "got an empty population");
+
+        let mut winner = choose_uniformly(rng, population);
+
+        for _ in 1..self.size {
+            let contender = choose_uniformly(rng, population);
+
+            if contender.fitness() > winner.fitness() {
+                winner = contender;
+            }
+        }
+
+        winner
+    }
+}
+
+/// Roulette wheel over individuals' ranks instead of their raw fitness - the
+/// least fit individual gets weight 1, the next one 2 and so on.
+///
+/// Keeps selection pressure steady no matter how (un)evenly fitness is
+/// spread, including when it's zero for everyone.
+pub struct RankSelection;
+
+impl SelectionMethod for RankSelection {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Candidate,
+    {
+        self.select_many(rng, population, 1)[0]
+    }
+
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Candidate,
+    {
+        assert!(!population.is_empty(), "got an empty population");
+
+        let ranked: Vec<_> = sorted_by_fitness(population)
+            .into_iter()
+            .enumerate()
+            .collect();
+
+        (0..count)
+            .map(|_| {
+                ranked
+                    .choose_weighted(rng, |(rank, _)| rank + 1)
+                    .expect("ranks are always positive")
+                    .1
+            })
+            .collect()
+    }
+}
+
+/// Fitness-proportionate selection that picks all the individuals with a
+/// single spin of a wheel with `count` evenly spaced pointers, so that
+/// individuals are chosen (almost) exactly as often as their fitness
+/// warrants.
+///
+/// If all the individuals have zero fitness, each of them is equally likely
+/// to be chosen; negative, infinite or NaN fitness is a bug and panics.
+pub struct StochasticUniversalSampling;
+
+impl SelectionMethod for StochasticUniversalSampling {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Candidate,
+    {
+        self.select_many(rng, population, 1)[0]
+    }
+
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Candidate,
+    {
+        assert!(!population.is_empty(), "got an empty population");
+
+        let total_fitness: f32 = population
+            .iter()
+            .map(|individual| {
+                let fitness = individual.fitness();
+
+                assert!(
+                    fitness.is_finite() && fitness >= 0.0,
+                    "got invalid fitness: {fitness}"
+                );
+
+                fitness
+            })
+            .sum();
+
+        assert!(total_fitness.is_finite(), "got too large total fitness");
+
+        if total_fitness == 0.0 || count == 0 {
+            return (0..count)
+                .map(|_| choose_uniformly(rng, population))
+                .collect();
+        }
+
+        let spacing = total_fitness / count as f32;
+        let offset = rng.random_range(0.0..spacing);
+
+        let mut selected = Vec::with_capacity(count);
+        let mut individuals = population.iter();
+        let mut individual = individuals.next().unwrap();
+        let mut cumulative_fitness = individual.fitness();
+
+        for pointer in 0..count {
+            let pointer = offset + pointer as f32 * spacing;
+
+            while cumulative_fitness <= pointer {
+                match individuals.next() {
+                    Some(next) => {
+                        individual = next;
+                        cumulative_fitness += individual.fitness();
+                    }
+
+                    // Rounding errors can make the last pointer overshoot
+                    None => break,
+                }
+            }
+
+            selected.push(individual);
+        }
+
+        // Otherwise consecutive picks (i.e. pairs of parents) would be mostly
+        // the same or neighbouring individuals
+        selected.shuffle(rng);
+        selected
+    }
+
+    fn selects_all_at_once(&self) -> bool {
+        true
+    }
+}
+
+/// Picks uniformly among the fittest `proportion` of the population, e.g.
+/// 0.25 means only the top quarter gets to reproduce.
+pub struct TruncationSelection {
+    proportion: f32,
+}
+
+impl TruncationSelection {
+    pub fn new(proportion: f32) -> Self {
+        assert!(proportion > 0.0 && proportion <= 1.0);
+
+        Self { proportion }
+    }
+}
+
+impl SelectionMethod for TruncationSelection {
+    fn select<'a, I>(&self, rng: &mut dyn RngCore, population: &'a [I]) -> &'a I
+    where
+        I: Candidate,
+    {
+        self.select_many(rng, population, 1)[0]
+    }
+
+    fn select_many<'a, I>(
+        &self,
+        rng: &mut dyn RngCore,
+        population: &'a [I],
+        count: usize,
+    ) -> Vec<&'a I>
+    where
+        I: Candidate,
     {
-        population
-            .choose_weighted(rng, |individual| individual.fitness())
-            .expect("Got an empty population")
+        assert!(!population.is_empty(), "got an empty population");
+
+        let ranked = sorted_by_fitness(population);
+        let kept = ((ranked.len() as f32 * self.proportion).ceil() as usize).max(1);
+        let fittest = &ranked[ranked.len() - kept..];
+
+        (0..count)
+            .map(|_| *choose_uniformly(rng, fittest))
+            .collect()
     }
 }
 
+fn choose_uniformly<'a, T>(rng: &mut dyn RngCore, items: &'a [T]) -> &'a T {
+    items.choose(rng).expect("got an empty population")
+}
+
+/// Returns individuals ordered from the least to the most fit one.
+fn sorted_by_fitness<I>(population: &[I]) -> Vec<&I>
+where
+    I: Candidate,
+{
+    let mut sorted: Vec<_> = population.iter().collect();
+
+    sorted.sort_by(|a, b| a.fitness().total_cmp(&b.fitness()));
+    sorted
+}
+
 pub trait CrossoverMethod {
     fn crossover(
         &self,
@@ -147,25 +644,300 @@ impl CrossoverMethod for UniformCrossover {
     }
 }
 
+/// Cuts both parents at a single random point and glues the beginning of
+/// `parent_a` to the end of `parent_b`.
+#[derive(Clone, Debug)]
+pub struct SinglePointCrossover;
+
+impl CrossoverMethod for SinglePointCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        let point = rng.random_range(0..=parent_a.len());
+
+        parent_a
+            .iter()
+            .take(point)
+            .chain(parent_b.iter().skip(point))
+            .copied()
+            .collect()
+    }
+}
+
+/// Cuts both parents at `points` distinct random points and alternates
+/// between them on each cut, starting with `parent_a`.
+#[derive(Clone, Debug)]
+pub struct MultiPointCrossover {
+    points: usize,
+}
+
+impl MultiPointCrossover {
+    pub fn new(points: usize) -> Self {
+        assert!(points > 0);
+
+        Self { points }
+    }
+}
+
+impl CrossoverMethod for MultiPointCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        // Cutting before the first gene or after the last one is pointless,
+        // hence `1..len`
+        let cuts = parent_a.len().saturating_sub(1);
+
+        let mut points: Vec<_> = rand::seq::index::sample(rng, cuts, self.points.min(cuts))
+            .into_iter()
+            .map(|point| point + 1)
+            .collect();
+
+        points.sort_unstable();
+
+        let mut points = points.into_iter().peekable();
+        let mut from_a = true;
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .enumerate()
+            .map(|(idx, (&a, &b))| {
+                if points.next_if_eq(&idx).is_some() {
+                    from_a = !from_a;
+                }
+
+                if from_a { a } else { b }
+            })
+            .collect()
+    }
+}
+
+/// Blends parents gene-by-gene: `child = alpha * a + (1 - alpha) * b`.
+#[derive(Clone, Debug)]
+pub struct ArithmeticCrossover {
+    alpha: f32,
+}
+
+impl ArithmeticCrossover {
+    pub fn new(alpha: f32) -> Self {
+        assert!((0.0..=1.0).contains(&alpha));
+
+        Self { alpha }
+    }
+}
+
+impl CrossoverMethod for ArithmeticCrossover {
+    fn crossover(
+        &self,
+        _rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .map(|(&a, &b)| self.alpha * a + (1.0 - self.alpha) * b)
+            .collect()
+    }
+}
+
+/// Blend crossover (BLX-α): each gene is drawn uniformly from the range
+/// spanned by both parents' genes, extended by `alpha` times its width on
+/// both sides.
+#[derive(Clone, Debug)]
+pub struct BlxAlphaCrossover {
+    alpha: f32,
+}
+
+impl BlxAlphaCrossover {
+    pub fn new(alpha: f32) -> Self {
+        assert!(alpha >= 0.0);
+
+        Self { alpha }
+    }
+}
+
+impl CrossoverMethod for BlxAlphaCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .map(|(&a, &b)| {
+                let (min, max) = (a.min(b), a.max(b));
+                let extent = self.alpha * (max - min);
+
+                rng.random_range((min - extent)..=(max + extent))
+            })
+            .collect()
+    }
+}
+
+/// Simulated binary crossover (SBX), which mimics the spread of offspring
+/// produced by single-point crossover on binary strings.
+///
+/// Higher `eta` keeps children closer to their parents.
+#[derive(Clone, Debug)]
+pub struct SimulatedBinaryCrossover {
+    eta: f32,
+}
+
+impl SimulatedBinaryCrossover {
+    pub fn new(eta: f32) -> Self {
+        assert!(eta >= 0.0);
+
+        Self { eta }
+    }
+}
+
+impl CrossoverMethod for SimulatedBinaryCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+
+        let exponent = 1.0 / (self.eta + 1.0);
+
+        parent_a
+            .iter()
+            .zip(parent_b.iter())
+            .map(|(&a, &b)| {
+                let u: f32 = rng.random();
+
+                let beta = if u <= 0.5 {
+                    libm::powf(2.0 * u, exponent)
+                } else {
+                    libm::powf(1.0 / (2.0 * (1.0 - u)), exponent)
+                };
+
+                0.5 * ((1.0 + beta) * a + (1.0 - beta) * b)
+            })
+            .collect()
+    }
+}
+
+/// Uniform crossover over groups of consecutive genes instead of single
+/// genes, so that each group is inherited as a whole from one parent.
+///
+/// Meant for chromosomes encoding neural networks, where shredding a neuron
+/// (its bias and incoming weights) between parents usually breaks it.
+///
+/// Chromosomes can carry extra genes past the last group (e.g. ones that
+/// describe something else than the network); those are inherited one by
+/// one, as in [`UniformCrossover`].
+#[derive(Clone, Debug)]
+pub struct NeuronCrossover {
+    /// Number of genes in each group, in order
+    groups: Vec<usize>,
+}
+
+impl NeuronCrossover {
+    pub fn new(groups: impl IntoIterator<Item = usize>) -> Self {
+        let groups: Vec<_> = groups.into_iter().collect();
+
+        assert!(groups.iter().all(|&group| group > 0));
+
+        Self { groups }
+    }
+
+    /// Creates a crossover for networks with given number of neurons per
+    /// layer, assuming genes are laid out neuron-by-neuron with bias first
+    /// (as in `Network::weights()`).
+    pub fn for_layers(neurons: &[usize]) -> Self {
+        Self::new(neurons.windows(2).flat_map(|layers| {
+            let (inputs, outputs) = (layers[0], layers[1]);
+
+            std::iter::repeat_n(1 + inputs, outputs)
+        }))
+    }
+}
+
+impl CrossoverMethod for NeuronCrossover {
+    fn crossover(
+        &self,
+        rng: &mut dyn RngCore,
+        parent_a: &Chromosome,
+        parent_b: &Chromosome,
+    ) -> Chromosome {
+        assert_eq!(parent_a.len(), parent_b.len());
+        assert!(
+            parent_a.len() >= self.groups.iter().sum::<usize>(),
+            "chromosome is shorter than its groups"
+        );
+
+        let mut genes = Vec::with_capacity(parent_a.len());
+
+        for &group in &self.groups {
+            let parent = if rng.random_bool(0.5) {
+                parent_a
+            } else {
+                parent_b
+            };
+
+            genes.extend_from_slice(&parent.genes[genes.len()..][..group]);
+        }
+
+        for idx in genes.len()..parent_a.len() {
+            let parent = if rng.random_bool(0.5) {
+                parent_a
+            } else {
+                parent_b
+            };
+
+            genes.push(parent[idx]);
+        }
+
+        Chromosome { genes }
+    }
+}
+
 pub trait MutationMethod {
     fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome);
+
+    /// Called by [`GeneticAlgorithm`] before each generation's `mutate()`s,
+    /// with the number of generations evolved so far.
+    fn set_generation(&mut self, _generation: usize) {}
 }
 
+/// Adds normally-distributed noise to genes.
+#[derive(Clone, Debug)]
 pub struct GaussianMutation {
     /// Probability of changing a gene:
     /// - 0.0 <- no genes will be touched
     /// - 1.0 <- all genes will be touched
     chance: f32,
 
-    /// Magnitude of that change:
+    /// Standard deviation of that change:
     /// - 0.0 <- touched genes will not be modified
-    /// - 3.0 <- touched genes will be += or -= by at most 3.0
+    /// - 3.0 <- touched genes will be += or -= by at most 3.0 in ~68% of
+    ///   cases
     coeff: f32,
 }
 
 impl GaussianMutation {
     pub fn new(chance: f32, coeff: f32) -> Self {
-        assert!(chance >= 0.0 && chance <= 1.0);
+        assert!((0.0..=1.0).contains(&chance));
 
         Self { chance, coeff }
     }
@@ -174,49 +946,146 @@ impl GaussianMutation {
 impl MutationMethod for GaussianMutation {
     fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
         for gene in child.iter_mut() {
-            let sign = if rng.random_bool(0.5) { -1.0 } else { 1.0 };
-
             if rng.random_bool(self.chance as f64) {
-                *gene += sign * self.coeff * rng.random::<f32>();
+                *gene += self.coeff * standard_normal(rng);
             }
         }
     }
 }
 
+/// Evolution-strategy-style mutation, where each individual carries its own
+/// step size that evolves together with the rest of its genes.
+///
+/// The step size is stored in the *last* gene of the chromosome, so
+/// individuals have to reserve it (e.g. initialize it to 0.1 or so) and
+/// ignore it when decoding themselves.
+///
+/// Each mutation first perturbs the step size log-normally and then adds
+/// noise of that size to all the remaining genes.
 #[derive(Clone, Debug)]
-pub struct Statistics {
-    pub min_fitness: f32,
-    pub max_fitness: f32,
-    pub avg_fitness: f32,
+pub struct SelfAdaptiveMutation {
+    /// Step sizes never go below this, so that the population can't freeze
+    min_step: f32,
 }
 
-impl Statistics {
-    fn new<I>(population: &[I]) -> Self
-    where
-        I: Individual,
-    {
-        assert!(!population.is_empty());
+impl SelfAdaptiveMutation {
+    pub fn new(min_step: f32) -> Self {
+        assert!(min_step > 0.0);
+
+        Self { min_step }
+    }
+}
+
+impl MutationMethod for SelfAdaptiveMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        let Some((step, genes)) = child.genes.split_last_mut() else {
+            return;
+        };
+
+        // Usual learning rate for a single step size, `1 / sqrt(n)`
+        let tau = 1.0 / libm::sqrtf(genes.len().max(1) as f32);
+
+        *step = (*step * libm::expf(tau * standard_normal(rng))).max(self.min_step);
+
+        for gene in genes {
+            *gene += *step * standard_normal(rng);
+        }
+    }
+}
+
+/// Replaces genes with fresh values drawn uniformly from `range` - usually
+/// the same range the initial population was drawn from.
+#[derive(Clone, Debug)]
+pub struct ResetMutation {
+    /// Probability of replacing a gene
+    chance: f32,
+    range: RangeInclusive<f32>,
+}
 
-        let mut min_fitness = population[0].fitness();
-        let mut max_fitness = min_fitness;
-        let mut sum_fitness = 0.0;
+impl ResetMutation {
+    pub fn new(chance: f32, range: RangeInclusive<f32>) -> Self {
+        assert!((0.0..=1.0).contains(&chance));
+        assert!(range.start() <= range.end());
 
-        for individual in population {
-            let fitness = individual.fitness();
+        Self { chance, range }
+    }
+}
 
-            min_fitness = min_fitness.min(fitness);
-            max_fitness = max_fitness.max(fitness);
-            sum_fitness += fitness;
+impl MutationMethod for ResetMutation {
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        for gene in child.iter_mut() {
+            if rng.random_bool(self.chance as f64) {
+                *gene = rng.random_range(self.range.clone());
+            }
         }
+    }
+}
+
+/// Anneals `chance` and `coeff` of another mutation method linearly from
+/// `from` to `to` over the first `generations` generations, and keeps them
+/// at `to` afterwards - e.g. to explore boldly at first and fine-tune later.
+///
+/// ```
+/// # use lib_genetic_algorithm::*;
+/// let mutation = ScheduledMutation::new(GaussianMutation::new, (0.1, 0.5), (0.01, 0.1), 100);
+/// ```
+pub struct ScheduledMutation<M, F> {
+    build: F,
+    from: (f32, f32),
+    to: (f32, f32),
+    generations: usize,
+    current: M,
+}
+
+impl<M, F> ScheduledMutation<M, F>
+where
+    M: MutationMethod,
+    F: Fn(f32, f32) -> M,
+{
+    pub fn new(build: F, from: (f32, f32), to: (f32, f32), generations: usize) -> Self {
+        assert!(generations > 0);
+
+        let current = build(from.0, from.1);
 
         Self {
-            min_fitness,
-            max_fitness,
-            avg_fitness: sum_fitness / (population.len() as f32),
+            build,
+            from,
+            to,
+            generations,
+            current,
         }
     }
 }
 
+impl<M, F> MutationMethod for ScheduledMutation<M, F>
+where
+    M: MutationMethod,
+    F: Fn(f32, f32) -> M,
+{
+    fn mutate(&self, rng: &mut dyn RngCore, child: &mut Chromosome) {
+        self.current.mutate(rng, child);
+    }
+
+    fn set_generation(&mut self, generation: usize) {
+        let t = generation.min(self.generations) as f32 / self.generations as f32;
+        let lerp = |from: f32, to: f32| from + (to - from) * t;
+
+        self.current = (self.build)(lerp(self.from.0, self.to.0), lerp(self.from.1, self.to.1));
+        self.current.set_generation(generation);
+    }
+}
+
+/// Draws a sample from the standard normal distribution using the
+/// Box-Muller transform; goes through `libm`, so that results are identical
+/// on every target.
+fn standard_normal(rng: &mut dyn RngCore) -> f32 {
+    // `1.0 - x` maps `[0, 1)` into `(0, 1]`, keeping the logarithm finite
+    let u1 = 1.0 - rng.random::<f32>();
+    let u2 = rng.random::<f32>();
+
+    libm::sqrtf(-2.0 * libm::logf(u1)) * libm::cosf(std::f32::consts::TAU * u2)
+}
+
 #[cfg(test)]
 mod tests {
     use rand::SeedableRng;
@@ -226,7 +1095,7 @@ mod tests {
     use std::collections::BTreeMap;
     use std::iter::FromIterator;
 
-    #[derive(Clone, Debug, PartialEq)]
+    #[derive(Clone, Debug)]
     enum TestIndividual {
         WithChromosome { chromosome: Chromosome },
 
@@ -239,11 +1108,7 @@ mod tests {
         }
     }
 
-    impl Individual for TestIndividual {
-        fn create(chromosome: Chromosome) -> Self {
-            Self::WithChromosome { chromosome }
-        }
-
+    impl Candidate for TestIndividual {
         fn chromosome(&self) -> &Chromosome {
             match self {
                 Self::WithChromosome { chromosome } => chromosome,
@@ -263,9 +1128,54 @@ mod tests {
         }
     }
 
-    impl PartialEq for Chromosome {
+    impl Individual for TestIndividual {
+        fn create(chromosome: Chromosome) -> Self {
+            Self::WithChromosome { chromosome }
+        }
+    }
+
+    /// Compares chromosomes approximately, so that golden values don't have
+    /// to spell out every last bit.
+    impl PartialEq for TestIndividual {
         fn eq(&self, other: &Self) -> bool {
-            approx::relative_eq!(self.genes.as_slice(), other.genes.as_slice())
+            match (self, other) {
+                (
+                    Self::WithChromosome { chromosome: a },
+                    Self::WithChromosome { chromosome: b },
+                ) => {
+                    approx::relative_eq!(a, b)
+                }
+
+                (Self::WithFitness { fitness: a }, Self::WithFitness { fitness: b }) => a == b,
+
+                _ => false,
+            }
+        }
+    }
+
+    impl approx::AbsDiffEq for Chromosome {
+        type Epsilon = f32;
+
+        fn default_epsilon() -> f32 {
+            f32::default_epsilon()
+        }
+
+        fn abs_diff_eq(&self, other: &Self, epsilon: f32) -> bool {
+            self.genes
+                .as_slice()
+                .abs_diff_eq(other.genes.as_slice(), epsilon)
+        }
+    }
+
+    impl approx::RelativeEq for Chromosome {
+        fn default_max_relative() -> f32 {
+            f32::default_max_relative()
+        }
+
+        fn relative_eq(&self, other: &Self, epsilon: f32, max_relative: f32) -> bool {
+            self.genes
+                .as_slice()
+                .relative_eq(other.genes.as_slice(), epsilon, max_relative)
         }
     }
 
@@ -299,6 +1209,94 @@ mod tests {
 
             assert_eq!(actual_histogram, expected_histogram);
         }
+
+        fn histogram(
+            selection_method: &impl SelectionMethod,
+            fitnesses: &[f32],
+        ) -> BTreeMap<i32, usize> {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let population: Vec<_> = fitnesses
+                .iter()
+                .map(|fitness| TestIndividual::new(*fitness))
+                .collect();
+
+            let mut histogram = BTreeMap::new();
+
+            for individual in selection_method.select_many(&mut rng, &population, 50) {
+                *histogram.entry(individual.fitness() as i32).or_insert(0) += 1;
+            }
+
+            histogram
+        }
+
+        #[test]
+        fn roulette_wheel_selection_with_zero_fitness() {
+            let actual = histogram(&RouletteWheelSelection, &[0.0, 0.0, 0.0]);
+
+            assert_eq!(actual, BTreeMap::from_iter([(0, 50)]));
+        }
+
+        #[test]
+        fn tournament_selection() {
+            let actual = histogram(&TournamentSelection::new(2), &[2.0, 1.0, 4.0, 3.0]);
+            let expected = BTreeMap::from_iter([(1, 7), (2, 7), (3, 8), (4, 28)]);
+
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn tournament_selection_of_one_is_uniform() {
+            let actual = histogram(&TournamentSelection::new(1), &[2.0, 1.0, 4.0, 3.0]);
+            let expected = BTreeMap::from_iter([(1, 10), (2, 12), (3, 12), (4, 16)]);
+
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn rank_selection() {
+            let actual = histogram(&RankSelection, &[2.0, 1.0, 40.0, 3.0]);
+            let expected = BTreeMap::from_iter([(1, 5), (2, 9), (3, 14), (40, 22)]);
+
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn stochastic_universal_sampling() {
+            let actual = histogram(&StochasticUniversalSampling, &[2.0, 1.0, 4.0, 3.0]);
+
+            // Each individual gets picked exactly `50 * fitness / 10` times
+            let expected = BTreeMap::from_iter([(1, 5), (2, 10), (3, 15), (4, 20)]);
+
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn stochastic_universal_sampling_with_zero_fitness() {
+            let actual = histogram(&StochasticUniversalSampling, &[0.0, 0.0, 0.0]);
+
+            assert_eq!(actual.values().sum::<usize>(), 50);
+        }
+
+        #[test]
+        #[should_panic(expected = "got invalid fitness")]
+        fn roulette_wheel_selection_with_negative_fitness() {
+            histogram(&RouletteWheelSelection, &[1.0, -1.0, 2.0]);
+        }
+
+        #[test]
+        #[should_panic(expected = "got invalid fitness")]
+        fn stochastic_universal_sampling_with_nan_fitness() {
+            histogram(&StochasticUniversalSampling, &[1.0, f32::NAN, 2.0]);
+        }
+
+        #[test]
+        fn truncation_selection() {
+            let actual = histogram(&TruncationSelection::new(0.5), &[2.0, 1.0, 4.0, 3.0]);
+            let expected = BTreeMap::from_iter([(3, 22), (4, 28)]);
+
+            assert_eq!(actual, expected);
+        }
     }
 
     mod crossover_methods {
@@ -320,6 +1318,134 @@ mod tests {
             assert_eq!(diff_a, 49);
             assert_eq!(diff_b, 51);
         }
+
+        fn parents() -> (Chromosome, Chromosome) {
+            let parent_a = (1..=10).map(|n| n as f32).collect();
+            let parent_b = (1..=10).map(|n| -n as f32).collect();
+
+            (parent_a, parent_b)
+        }
+
+        #[test]
+        fn single_point_crossover() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let (parent_a, parent_b) = parents();
+            let child = SinglePointCrossover.crossover(&mut rng, &parent_a, &parent_b);
+
+            let expected: Chromosome = [1.0, 2.0, -3.0, -4.0, -5.0, -6.0, -7.0, -8.0, -9.0, -10.0]
+                .into_iter()
+                .collect();
+
+            approx::assert_relative_eq!(child, expected);
+        }
+
+        #[test]
+        fn multi_point_crossover() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let (parent_a, parent_b) = parents();
+            let child = MultiPointCrossover::new(3).crossover(&mut rng, &parent_a, &parent_b);
+
+            let expected: Chromosome = [1.0, 2.0, -3.0, -4.0, -5.0, -6.0, -7.0, 8.0, 9.0, -10.0]
+                .into_iter()
+                .collect();
+
+            approx::assert_relative_eq!(child, expected);
+        }
+
+        #[test]
+        fn arithmetic_crossover() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let (parent_a, parent_b) = parents();
+            let child = ArithmeticCrossover::new(0.75).crossover(&mut rng, &parent_a, &parent_b);
+
+            let expected: Chromosome = (1..=10).map(|n| n as f32 * 0.5).collect();
+
+            approx::assert_relative_eq!(child, expected);
+        }
+
+        #[test]
+        fn blx_alpha_crossover() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let (parent_a, parent_b) = parents();
+            let child = BlxAlphaCrossover::new(0.5).crossover(&mut rng, &parent_a, &parent_b);
+
+            let expected: Chromosome = [
+                -1.2510376, 2.6953573, 4.9087563, 2.1027908, 5.238805, -6.422021, 0.97117424,
+                -12.237091, -1.8449898, -9.775925,
+            ]
+            .into_iter()
+            .collect();
+
+            approx::assert_relative_eq!(child, expected);
+
+            for (gene, n) in child.iter().zip(1..) {
+                let bound = 2.0 * n as f32;
+
+                assert!((-bound..=bound).contains(gene));
+            }
+        }
+
+        #[test]
+        fn simulated_binary_crossover() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let (parent_a, parent_b) = parents();
+            let child =
+                SimulatedBinaryCrossover::new(2.0).crossover(&mut rng, &parent_a, &parent_b);
+
+            let expected: Chromosome = [
+                0.72079206, 2.9054909, 5.294982, 4.4280024, 6.4032135, 4.6478252, 7.169777,
+                4.938078, 8.681354, 7.9958515,
+            ]
+            .into_iter()
+            .collect();
+
+            approx::assert_relative_eq!(child, expected);
+        }
+
+        #[test]
+        fn neuron_crossover() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let (parent_a, parent_b) = parents();
+
+            // 1 input -> 2 neurons (2 genes each) -> 2 neurons (3 genes each)
+            let crossover = NeuronCrossover::for_layers(&[1, 2, 2]);
+            let child = crossover.crossover(&mut rng, &parent_a, &parent_b);
+
+            let expected: Chromosome = [-1.0, -2.0, -3.0, -4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0]
+                .into_iter()
+                .collect();
+
+            approx::assert_relative_eq!(child, expected);
+        }
+
+        #[test]
+        fn neuron_crossover_with_extra_genes() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let (parent_a, parent_b) = parents();
+
+            // 1 input -> 2 neurons (2 genes each), followed by 6 extra genes
+            let crossover = NeuronCrossover::for_layers(&[1, 2]);
+            let child = crossover.crossover(&mut rng, &parent_a, &parent_b);
+
+            assert_eq!(child.len(), parent_a.len());
+
+            for group in child.genes[..4].chunks_exact(2) {
+                assert_eq!(group[0].signum(), group[1].signum());
+            }
+
+            for (idx, gene) in child.iter().enumerate() {
+                assert!(*gene == parent_a[idx] || *gene == parent_b[idx]);
+            }
+        }
+
+        #[test]
+        #[should_panic(expected = "chromosome is shorter than its groups")]
+        fn neuron_crossover_with_missing_genes() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let (parent_a, parent_b) = parents();
+
+            NeuronCrossover::for_layers(&[2, 4]).crossover(&mut rng, &parent_a, &parent_b);
+        }
     }
 
     mod gaussian_mutation {
@@ -394,7 +1520,7 @@ mod tests {
                 #[test]
                 fn slightly_changes_the_original_chromosome() {
                     let actual = actual(0.5);
-                    let expected = vec![1.0, 1.7756249, 3.0, 4.1596804, 5.0];
+                    let expected = vec![1.0, 2.0, 3.4572105, 4.413191, 4.9412436];
 
                     assert_relative_eq!(actual.as_slice(), expected.as_slice());
                 }
@@ -426,7 +1552,7 @@ mod tests {
                 #[test]
                 fn entirely_changes_the_original_chromosome() {
                     let actual = actual(0.5);
-                    let expected = vec![1.4545316, 2.1162078, 2.7756248, 3.9505124, 4.638691];
+                    let expected = vec![1.1672232, 1.2576667, 3.0934038, 4.4572105, 4.9807963];
 
                     assert_relative_eq!(actual.as_slice(), expected.as_slice());
                 }
@@ -434,6 +1560,87 @@ mod tests {
         }
     }
 
+    mod mutation_methods {
+        use approx::assert_relative_eq;
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+
+        use super::*;
+
+        fn mutate(method: &impl MutationMethod, genes: &[f32]) -> Vec<f32> {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let mut child = genes.iter().copied().collect();
+
+            method.mutate(&mut rng, &mut child);
+
+            child.into_iter().collect()
+        }
+
+        #[test]
+        fn gaussian_mutation_is_normally_distributed() {
+            let actual = mutate(&GaussianMutation::new(1.0, 2.0), &[0.0; 10_000]);
+
+            let mean = actual.iter().sum::<f32>() / actual.len() as f32;
+            let variance =
+                actual.iter().map(|x| (x - mean).powi(2)).sum::<f32>() / actual.len() as f32;
+            let within_one_sigma = actual.iter().filter(|x| x.abs() <= 2.0).count();
+
+            assert_relative_eq!(mean, 0.0, epsilon = 0.05);
+            assert_relative_eq!(variance.sqrt(), 2.0, epsilon = 0.05);
+            assert_relative_eq!(within_one_sigma as f32 / 10_000.0, 0.6827, epsilon = 0.01);
+        }
+
+        #[test]
+        fn self_adaptive_mutation() {
+            let actual = mutate(&SelfAdaptiveMutation::new(0.01), &[1.0, 2.0, 3.0, 4.0, 0.5]);
+            let expected = vec![0.12254864, 2.110405, 3.5404313, 3.9773011, 0.591009];
+
+            assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+
+        #[test]
+        fn self_adaptive_mutation_respects_min_step() {
+            let actual = mutate(&SelfAdaptiveMutation::new(0.25), &[1.0, 2.0, 0.0]);
+
+            assert_eq!(actual[2], 0.25);
+        }
+
+        #[test]
+        fn reset_mutation() {
+            let actual = mutate(
+                &ResetMutation::new(0.5, -1.0..=1.0),
+                &[10.0, 20.0, 30.0, 40.0, 50.0],
+            );
+
+            let expected = vec![10.0, 20.0, 0.069369555, -0.48879623, 0.27546048];
+
+            assert_relative_eq!(actual.as_slice(), expected.as_slice());
+        }
+
+        #[test]
+        fn scheduled_mutation() {
+            let mut method =
+                ScheduledMutation::new(GaussianMutation::new, (1.0, 1.0), (0.0, 0.0), 4);
+
+            let genes = [1.0, 2.0, 3.0];
+
+            let mut actual = Vec::new();
+
+            for generation in [0, 2, 4, 10] {
+                method.set_generation(generation);
+                actual.push(mutate(&method, &genes));
+            }
+
+            // Halfway through, the mutation should be exactly as if it was
+            // configured with halved parameters
+            assert_eq!(actual[1], mutate(&GaussianMutation::new(0.5, 0.5), &genes));
+
+            assert_eq!(actual[0], mutate(&GaussianMutation::new(1.0, 1.0), &genes));
+            assert_eq!(actual[2], genes);
+            assert_eq!(actual[3], genes);
+        }
+    }
+
     #[test]
     fn genetic_algorithm() {
         fn individual(genes: &[f32]) -> TestIndividual {
@@ -442,7 +1649,7 @@ mod tests {
 
         let mut rng = ChaCha8Rng::from_seed(Default::default());
 
-        let ga = GeneticAlgorithm::new(
+        let mut ga = GeneticAlgorithm::new(
             RouletteWheelSelection,
             UniformCrossover,
             GaussianMutation::new(0.5, 0.5),
@@ -460,12 +1667,127 @@ mod tests {
         }
 
         let expected_population = vec![
-            individual(&[0.44769490, 2.0648358, 4.3058133]),
-            individual(&[1.21268670, 1.5538777, 2.8869110]),
-            individual(&[1.06176780, 2.2657390, 4.4287640]),
-            individual(&[0.95909685, 2.4618788, 4.0247330]),
+            individual(&[1.1816709, 4.1556053, 1.9139526]),
+            individual(&[1.0810513, 4.1805573, 1.9190462]),
+            individual(&[0.7062351, 3.7581184, 1.9139526]),
+            individual(&[-0.2788526, 3.7581184, 2.213854]),
         ];
 
         assert_eq!(population, expected_population);
     }
+
+    mod replacement {
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+
+        use super::*;
+
+        fn individual(genes: &[f32]) -> TestIndividual {
+            TestIndividual::create(genes.iter().cloned().collect())
+        }
+
+        fn evolve(
+            ga: GeneticAlgorithm<RouletteWheelSelection>,
+        ) -> (Vec<TestIndividual>, Statistics) {
+            let mut ga = ga;
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            let population = vec![
+                individual(&[0.0, 0.0, 0.0]),
+                individual(&[1.0, 1.0, 1.0]),
+                individual(&[1.0, 2.0, 4.0]),
+                individual(&[1.0, 2.0, 1.0]),
+            ];
+
+            ga.evolve(&mut rng, &population)
+        }
+
+        fn ga() -> GeneticAlgorithm<RouletteWheelSelection> {
+            // No mutations, so that offspring's genes come straight from
+            // their parents
+            GeneticAlgorithm::new(
+                RouletteWheelSelection,
+                UniformCrossover,
+                GaussianMutation::new(0.0, 0.0),
+            )
+        }
+
+        #[test]
+        fn elitism() {
+            let (population, stats) = evolve(ga().with_elitism(2));
+
+            assert_eq!(population[0], individual(&[1.0, 2.0, 4.0]));
+            assert_eq!(population[1], individual(&[1.0, 2.0, 1.0]));
+            assert_eq!(population.len(), 4);
+            assert_eq!((stats.survivors, stats.offspring), (2, 2));
+        }
+
+        #[test]
+        fn steady_state() {
+            let (population, stats) =
+                evolve(ga().with_replacement(Replacement::SteadyState { count: 1 }));
+
+            assert_eq!(
+                population[..3],
+                [
+                    individual(&[1.0, 2.0, 4.0]),
+                    individual(&[1.0, 2.0, 1.0]),
+                    individual(&[1.0, 1.0, 1.0]),
+                ]
+            );
+            assert_eq!(population.len(), 4);
+            assert_eq!((stats.survivors, stats.offspring), (3, 1));
+        }
+
+        #[test]
+        fn mu_plus_lambda() {
+            let (population, stats) =
+                evolve(ga().with_replacement(Replacement::MuPlusLambda { mu: 1 }));
+
+            // With a single breeder, all the offspring are its clones
+            assert_eq!(population, vec![individual(&[1.0, 2.0, 4.0]); 4]);
+            assert_eq!((stats.survivors, stats.offspring), (1, 3));
+        }
+
+        #[test]
+        fn mu_comma_lambda() {
+            let (population, stats) =
+                evolve(ga().with_replacement(Replacement::MuCommaLambda { mu: 2 }));
+
+            for child in &population {
+                for (gene, (a, b)) in
+                    child
+                        .chromosome()
+                        .iter()
+                        .zip([(1.0, 1.0), (2.0, 2.0), (4.0, 1.0)])
+                {
+                    assert!(*gene == a || *gene == b);
+                }
+            }
+
+            assert_eq!(population.len(), 4);
+            assert_eq!((stats.survivors, stats.offspring), (0, 4));
+        }
+
+        #[test]
+        fn speciation() {
+            let (population, stats) =
+                evolve(ga().with_speciation(Speciation::new(EuclideanDistance, 1.5)));
+
+            // Only [1, 1, 1] and [1, 2, 1] are within 1.5 from each other
+            assert_eq!(stats.species_sizes, [1, 2, 1]);
+            assert_eq!(population.len(), 4);
+        }
+
+        #[test]
+        fn mu_comma_lambda_with_elitism() {
+            let (population, stats) = evolve(
+                ga().with_elitism(1)
+                    .with_replacement(Replacement::MuCommaLambda { mu: 2 }),
+            );
+
+            assert_eq!(population[0], individual(&[1.0, 2.0, 4.0]));
+            assert_eq!((stats.survivors, stats.offspring), (1, 3));
+        }
+    }
 }
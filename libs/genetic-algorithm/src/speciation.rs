@@ -0,0 +1,269 @@
+use crate::*;
+
+/// Measures how different two chromosomes are.
+pub trait DistanceMetric {
+    fn distance(&self, a: &Chromosome, b: &Chromosome) -> f32;
+}
+
+#[derive(Clone, Debug)]
+pub struct EuclideanDistance;
+
+impl DistanceMetric for EuclideanDistance {
+    fn distance(&self, a: &Chromosome, b: &Chromosome) -> f32 {
+        assert_eq!(a.len(), b.len());
+
+        let sum: f32 = a.iter().zip(b.iter()).map(|(a, b)| (a - b).powi(2)).sum();
+
+        libm::sqrtf(sum)
+    }
+}
+
+/// Mean absolute difference between genes, i.e. NEAT's "average weight
+/// difference"; unlike [`EuclideanDistance`], it doesn't grow with the
+/// number of genes.
+#[derive(Clone, Debug)]
+pub struct MeanAbsoluteDistance;
+
+impl DistanceMetric for MeanAbsoluteDistance {
+    fn distance(&self, a: &Chromosome, b: &Chromosome) -> f32 {
+        assert_eq!(a.len(), b.len());
+
+        if a.is_empty() {
+            return 0.0;
+        }
+
+        let sum: f32 = a.iter().zip(b.iter()).map(|(a, b)| (a - b).abs()).sum();
+
+        sum / a.len() as f32
+    }
+}
+
+/// Splits the population into species of similar chromosomes and makes
+/// individuals compete mostly within their own species, so that a single
+/// behaviour can't take over the entire population right away.
+///
+/// Each individual joins the first species whose representative is closer
+/// than `threshold`, or founds a new species. Species persist across
+/// generations (represented by a member of the previous generation) until
+/// they run out of members.
+///
+/// Selection then sees *shared* fitness - fitness divided by the size of the
+/// individual's species - with members of species younger than
+/// `protection` generations getting it multiplied by `boost`, so that fresh
+/// innovations have a chance to get optimized before they are out-competed.
+pub struct Speciation {
+    metric: Box<dyn DistanceMetric + Send + Sync>,
+    threshold: f32,
+    protection: usize,
+    boost: f32,
+    species: Vec<Species>,
+}
+
+/// Species remembered between generations.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Species {
+    representative: Chromosome,
+
+    /// Number of generations this species has existed for
+    age: usize,
+
+    /// Indices of members within the current population
+    #[serde(skip)]
+    members: Vec<usize>,
+}
+
+impl Species {
+    pub fn representative(&self) -> &Chromosome {
+        &self.representative
+    }
+}
+
+/// Outcome of [`Speciation::speciate()`].
+pub(crate) struct Speciated {
+    /// Shared fitness of each individual, in population order
+    pub(crate) fitness: Vec<f32>,
+
+    pub(crate) species_sizes: Vec<usize>,
+}
+
+impl Speciation {
+    pub fn new(metric: impl DistanceMetric + Send + Sync + 'static, threshold: f32) -> Self {
+        assert!(threshold > 0.0);
+
+        Self {
+            metric: Box::new(metric),
+            threshold,
+            protection: 0,
+            boost: 1.0,
+            species: Vec::new(),
+        }
+    }
+
+    /// Multiplies shared fitness of species younger than `generations` by
+    /// `boost`.
+    pub fn with_protection(mut self, generations: usize, boost: f32) -> Self {
+        assert!(boost >= 1.0);
+
+        self.protection = generations;
+        self.boost = boost;
+        self
+    }
+
+    /// Species found in the most recent generation.
+    pub fn species(&self) -> &[Species] {
+        &self.species
+    }
+
+    /// Replaces remembered species, e.g. when resuming an interrupted run.
+    pub fn set_species(&mut self, species: Vec<Species>) {
+        self.species = species;
+    }
+
+    pub(crate) fn speciate<I>(&mut self, population: &[I]) -> Speciated
+    where
+        I: Candidate,
+    {
+        for species in &mut self.species {
+            species.members.clear();
+        }
+
+        for (idx, individual) in population.iter().enumerate() {
+            let chromosome = individual.chromosome();
+
+            let species = self.species.iter_mut().find(|species| {
+                self.metric.distance(&species.representative, chromosome) < self.threshold
+            });
+
+            match species {
+                Some(species) => species.members.push(idx),
+
+                None => self.species.push(Species {
+                    representative: chromosome.clone(),
+                    age: 0,
+                    members: vec![idx],
+                }),
+            }
+        }
+
+        self.species.retain(|species| !species.members.is_empty());
+
+        let mut fitness = vec![0.0; population.len()];
+
+        for species in &mut self.species {
+            let boost = if species.age < self.protection {
+                self.boost
+            } else {
+                1.0
+            };
+
+            let size = species.members.len() as f32;
+
+            for &idx in &species.members {
+                fitness[idx] = population[idx].fitness() / size * boost;
+            }
+
+            species.representative = population[species.members[0]].chromosome().clone();
+            species.age += 1;
+        }
+
+        Speciated {
+            fitness,
+            species_sizes: self
+                .species
+                .iter()
+                .map(|species| species.members.len())
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    struct TestIndividual {
+        chromosome: Chromosome,
+    }
+
+    impl Candidate for TestIndividual {
+        fn chromosome(&self) -> &Chromosome {
+            &self.chromosome
+        }
+
+        fn fitness(&self) -> f32 {
+            self.chromosome[1]
+        }
+    }
+
+    impl Individual for TestIndividual {
+        fn create(chromosome: Chromosome) -> Self {
+            Self { chromosome }
+        }
+    }
+
+    /// First gene decides the species, second one is the fitness.
+    fn population(individuals: &[(f32, f32)]) -> Vec<TestIndividual> {
+        individuals
+            .iter()
+            .map(|&(gene, fitness)| TestIndividual::create([gene, fitness].into_iter().collect()))
+            .collect()
+    }
+
+    #[test]
+    fn distance_metrics() {
+        let a: Chromosome = [0.0, 0.0, 0.0, 0.0].into_iter().collect();
+        let b: Chromosome = [1.0, -1.0, 1.0, -3.0].into_iter().collect();
+
+        assert_relative_eq!(EuclideanDistance.distance(&a, &b), 12f32.sqrt());
+        assert_relative_eq!(MeanAbsoluteDistance.distance(&a, &b), 1.5);
+    }
+
+    #[test]
+    fn shares_fitness_within_species() {
+        let mut speciation = Speciation::new(MeanAbsoluteDistance, 5.0);
+
+        let speciated = speciation.speciate(&population(&[
+            (0.0, 2.0),
+            (100.0, 3.0),
+            (1.0, 4.0),
+            (2.0, 6.0),
+        ]));
+
+        assert_eq!(speciated.species_sizes, [3, 1]);
+        assert_eq!(speciated.fitness, [2.0 / 3.0, 3.0, 4.0 / 3.0, 2.0]);
+        assert_eq!(speciation.species().len(), 2);
+    }
+
+    #[test]
+    fn species_persist_across_generations() {
+        let mut speciation = Speciation::new(MeanAbsoluteDistance, 5.0);
+
+        speciation.speciate(&population(&[(0.0, 1.0), (100.0, 1.0)]));
+
+        // Species are kept in order of their appearance, so if the first one
+        // is remembered, it comes first even though its member doesn't
+        let speciated = speciation.speciate(&population(&[(200.0, 1.0), (2.0, 1.0)]));
+
+        assert_eq!(speciated.species_sizes, [1, 1]);
+        assert_eq!(speciation.species[0].members, [1]);
+        assert_eq!(speciation.species[0].age, 2);
+        assert_eq!(speciation.species[1].age, 1);
+
+        // Species of `100.0` died out, while `200.0` founded a new one
+        assert_eq!(speciation.species.len(), 2);
+    }
+
+    #[test]
+    fn protects_young_species() {
+        let mut speciation = Speciation::new(MeanAbsoluteDistance, 5.0).with_protection(1, 2.0);
+
+        let speciated = speciation.speciate(&population(&[(0.0, 1.0)]));
+
+        assert_eq!(speciated.fitness, [2.0]);
+
+        let speciated = speciation.speciate(&population(&[(0.0, 1.0), (100.0, 1.0)]));
+
+        assert_eq!(speciated.fitness, [1.0, 2.0]);
+    }
+}
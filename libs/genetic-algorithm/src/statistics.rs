@@ -0,0 +1,365 @@
+use crate::*;
+use std::io::{self, Write};
+
+/// Summary of a population, as evaluated right before it got evolved.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Statistics {
+    /// Number of generations evolved before this one
+    pub generation: usize,
+
+    pub min_fitness: f32,
+    pub max_fitness: f32,
+    pub avg_fitness: f32,
+    pub median_fitness: f32,
+    pub std_dev_fitness: f32,
+
+    /// Fitness below which 10%, 25%, 75% and 90% of the population falls
+    pub p10_fitness: f32,
+    pub p25_fitness: f32,
+    pub p75_fitness: f32,
+    pub p90_fitness: f32,
+
+    /// Mean Euclidean distance between chromosomes of all pairs of
+    /// individuals; drops towards zero as the population converges
+    pub mean_distance: f32,
+
+    /// Variance of each gene across the population
+    pub gene_variance: Vec<f32>,
+
+    /// Index of the fittest individual within the evaluated population
+    pub best_index: usize,
+    pub best_chromosome: Chromosome,
+
+    /// Number of individuals carried unchanged into the next generation
+    pub survivors: usize,
+
+    /// Number of individuals bred for the next generation
+    pub offspring: usize,
+
+    /// Number of members of each species; empty if speciation is disabled
+    pub species_sizes: Vec<usize>,
+}
+
+impl Statistics {
+    pub(crate) fn new<I>(
+        generation: usize,
+        population: &[I],
+        survivors: usize,
+        offspring: usize,
+    ) -> Self
+    where
+        I: Candidate,
+    {
+        assert!(!population.is_empty());
+
+        let mut min_fitness = population[0].fitness();
+        let mut max_fitness = min_fitness;
+        let mut sum_fitness = 0.0;
+        let mut best_index = 0;
+
+        for (idx, individual) in population.iter().enumerate() {
+            let fitness = individual.fitness();
+
+            if fitness > max_fitness {
+                best_index = idx;
+            }
+
+            min_fitness = min_fitness.min(fitness);
+            max_fitness = max_fitness.max(fitness);
+            sum_fitness += fitness;
+        }
+
+        let len = population.len() as f32;
+        let avg_fitness = sum_fitness / len;
+
+        let variance = population
+            .iter()
+            .map(|individual| (individual.fitness() - avg_fitness).powi(2))
+            .sum::<f32>()
+            / len;
+
+        let mut fitnesses: Vec<_> = population
+            .iter()
+            .map(|individual| individual.fitness())
+            .collect();
+
+        fitnesses.sort_by(f32::total_cmp);
+
+        Self {
+            generation,
+            min_fitness,
+            max_fitness,
+            avg_fitness,
+            median_fitness: percentile(&fitnesses, 0.5),
+            std_dev_fitness: libm::sqrtf(variance),
+            p10_fitness: percentile(&fitnesses, 0.1),
+            p25_fitness: percentile(&fitnesses, 0.25),
+            p75_fitness: percentile(&fitnesses, 0.75),
+            p90_fitness: percentile(&fitnesses, 0.9),
+            mean_distance: mean_distance(population),
+            gene_variance: gene_variance(population),
+            best_index,
+            best_chromosome: population[best_index].chromosome().clone(),
+            survivors,
+            offspring,
+            species_sizes: Vec::new(),
+        }
+    }
+
+    /// Writes a single line of [`History::write_csv()`].
+    pub fn write_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(
+            writer,
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            self.generation,
+            self.min_fitness,
+            self.max_fitness,
+            self.avg_fitness,
+            self.median_fitness,
+            self.std_dev_fitness,
+            self.p10_fitness,
+            self.p25_fitness,
+            self.p75_fitness,
+            self.p90_fitness,
+            self.mean_distance,
+            self.best_index,
+            self.survivors,
+            self.offspring,
+            self.species_sizes.len(),
+        )
+    }
+}
+
+/// Returns fitness below which given fraction of the (sorted ascending, non
+/// empty) `fitnesses` falls, e.g. `percentile(.., 0.9)` is the 90th
+/// percentile; interpolates linearly between individuals.
+fn percentile(fitnesses: &[f32], p: f32) -> f32 {
+    let rank = p * (fitnesses.len() - 1) as f32;
+    let lo = rank as usize;
+    let hi = (lo + 1).min(fitnesses.len() - 1);
+
+    let (lo_fitness, hi_fitness) = (fitnesses[lo], fitnesses[hi]);
+
+    lo_fitness + (hi_fitness - lo_fitness) * (rank - lo as f32)
+}
+
+fn mean_distance<I>(population: &[I]) -> f32
+where
+    I: Candidate,
+{
+    let mut sum = 0.0;
+    let mut pairs = 0;
+
+    for (idx, a) in population.iter().enumerate() {
+        for b in &population[idx + 1..] {
+            let distance = a
+                .chromosome()
+                .iter()
+                .zip(b.chromosome().iter())
+                .map(|(a, b)| (a - b).powi(2))
+                .sum::<f32>();
+
+            sum += libm::sqrtf(distance);
+            pairs += 1;
+        }
+    }
+
+    if pairs == 0 { 0.0 } else { sum / pairs as f32 }
+}
+
+fn gene_variance<I>(population: &[I]) -> Vec<f32>
+where
+    I: Candidate,
+{
+    let len = population.len() as f32;
+    let genes = population[0].chromosome().len();
+
+    let mut sum = vec![0.0; genes];
+    let mut sum_sq = vec![0.0; genes];
+
+    for individual in population {
+        for (idx, gene) in individual.chromosome().iter().enumerate() {
+            sum[idx] += gene;
+            sum_sq[idx] += gene * gene;
+        }
+    }
+
+    sum.iter()
+        .zip(sum_sq)
+        .map(|(sum, sum_sq)| {
+            let mean = sum / len;
+
+            // Clamped, because rounding errors can make it slightly negative
+            (sum_sq / len - mean * mean).max(0.0)
+        })
+        .collect()
+}
+
+/// Statistics of consecutive generations of a single run.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct History {
+    generations: Vec<Statistics>,
+}
+
+impl History {
+    pub fn push(&mut self, stats: Statistics) {
+        self.generations.push(stats);
+    }
+
+    pub fn len(&self) -> usize {
+        self.generations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.generations.is_empty()
+    }
+
+    pub fn last(&self) -> Option<&Statistics> {
+        self.generations.last()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Statistics> {
+        self.generations.iter()
+    }
+
+    /// Writes one line per generation with all the scalar statistics (i.e.
+    /// without per-gene variance and the best chromosome), preceded by a
+    /// header.
+    pub fn write_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        Self::write_csv_header(&mut writer)?;
+
+        for stats in &self.generations {
+            stats.write_csv(&mut writer)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes just the header of `write_csv()`, e.g. to append lines of
+    /// [`Statistics::write_csv()`] to it as generations get evolved.
+    pub fn write_csv_header(mut writer: impl Write) -> io::Result<()> {
+        writeln!(
+            writer,
+            "generation,min_fitness,max_fitness,avg_fitness,median_fitness,std_dev_fitness,\
+             p10_fitness,p25_fitness,p75_fitness,p90_fitness,mean_distance,best_index,\
+             survivors,offspring,species"
+        )
+    }
+
+    pub fn to_csv(&self) -> String {
+        let mut csv = Vec::new();
+
+        self.write_csv(&mut csv)
+            .expect("writing into a Vec never fails");
+
+        String::from_utf8(csv).expect("CSV is always valid UTF-8")
+    }
+
+    /// Serializes all the statistics, including per-gene variance and the
+    /// best chromosomes.
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+impl<'a> IntoIterator for &'a History {
+    type Item = &'a Statistics;
+    type IntoIter = std::slice::Iter<'a, Statistics>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.generations.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    struct TestIndividual {
+        chromosome: Chromosome,
+    }
+
+    impl Candidate for TestIndividual {
+        fn chromosome(&self) -> &Chromosome {
+            &self.chromosome
+        }
+
+        fn fitness(&self) -> f32 {
+            self.chromosome.iter().sum()
+        }
+    }
+
+    impl Individual for TestIndividual {
+        fn create(chromosome: Chromosome) -> Self {
+            Self { chromosome }
+        }
+    }
+
+    fn stats(generation: usize) -> Statistics {
+        let population: Vec<_> = [[0.0, 0.0], [1.0, 0.0], [3.0, 4.0], [1.0, 1.0]]
+            .into_iter()
+            .map(|genes| TestIndividual::create(genes.into_iter().collect()))
+            .collect();
+
+        Statistics::new(generation, &population, 1, 3)
+    }
+
+    #[test]
+    fn statistics() {
+        let stats = stats(0);
+
+        assert_eq!(stats.min_fitness, 0.0);
+        assert_eq!(stats.max_fitness, 7.0);
+        assert_eq!(stats.avg_fitness, 2.5);
+        assert_eq!(stats.median_fitness, 1.5);
+        assert_relative_eq!(stats.std_dev_fitness, 2.6925824);
+        assert_relative_eq!(stats.p10_fitness, 0.3);
+        assert_relative_eq!(stats.p25_fitness, 0.75);
+        assert_relative_eq!(stats.p75_fitness, 3.25);
+        assert_relative_eq!(stats.p90_fitness, 5.5, epsilon = 1e-5);
+        assert_eq!(stats.best_index, 2);
+        assert_eq!(stats.best_chromosome, [3.0, 4.0].into_iter().collect());
+
+        // Per-gene means are 1.25 and 1.25
+        assert_relative_eq!(stats.gene_variance.as_slice(), [1.1875, 2.6875].as_slice());
+
+        // Distances: 1, 5, √2, √20, 1, √13
+        let expected = (1.0 + 5.0 + 2f32.sqrt() + 20f32.sqrt() + 1.0 + 13f32.sqrt()) / 6.0;
+
+        assert_relative_eq!(stats.mean_distance, expected);
+    }
+
+    #[test]
+    fn history_csv() {
+        let mut history = History::default();
+
+        history.push(stats(0));
+        history.push(stats(1));
+
+        let csv = history.to_csv();
+        let lines: Vec<_> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("generation,min_fitness,"));
+        assert!(lines[2].starts_with("1,0,7,2.5,1.5,"));
+        assert!(lines[2].ends_with(",2,1,3,0"));
+    }
+
+    #[test]
+    fn history_json_roundtrip() {
+        let mut history = History::default();
+
+        history.push(stats(0));
+        history.push(stats(1));
+
+        let json = history.to_json().unwrap();
+
+        assert_eq!(History::from_json(&json).unwrap(), history);
+    }
+}
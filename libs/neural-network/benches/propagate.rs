@@ -0,0 +1,78 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use lib_neural_network::{Activation, Buffers, LayerTopology, Network, NetworkBatch};
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::hint::black_box;
+
+/// Same shape as the simulation's brains (9 eye cells, 18 hidden neurons).
+fn network() -> Network {
+    let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+    random_network(&mut rng)
+}
+
+fn random_network(rng: &mut ChaCha8Rng) -> Network {
+    Network::random(
+        rng,
+        &[
+            LayerTopology::new(9),
+            LayerTopology::new(18),
+            LayerTopology {
+                neurons: 2,
+                activation: Activation::Tanh,
+            },
+        ],
+    )
+}
+
+fn propagate(c: &mut Criterion) {
+    let network = network();
+    let inputs: Vec<_> = (0..9).map(|i| i as f32 / 9.0).collect();
+
+    c.bench_function("propagate", |b| {
+        b.iter(|| network.propagate(black_box(inputs.clone())))
+    });
+}
+
+fn propagate_into(c: &mut Criterion) {
+    let network = network();
+    let inputs: Vec<_> = (0..9).map(|i| i as f32 / 9.0).collect();
+    let mut buffers = Buffers::default();
+
+    c.bench_function("propagate_into", |b| {
+        b.iter(|| {
+            network
+                .propagate_into(black_box(&inputs), &mut buffers)
+                .map(|outputs| outputs[0])
+        })
+    });
+}
+
+/// 40 brains, one step of the simulation.
+fn propagate_many(c: &mut Criterion) {
+    let mut rng = ChaCha8Rng::from_seed(Default::default());
+    let networks: Vec<_> = (0..40).map(|_| random_network(&mut rng)).collect();
+    let inputs: Vec<_> = (0..40 * 9).map(|i| (i % 9) as f32 / 9.0).collect();
+    let mut buffers = Buffers::default();
+
+    c.bench_function("propagate_many/one_by_one", |b| {
+        b.iter(|| {
+            for (network, inputs) in networks.iter().zip(inputs.chunks_exact(9)) {
+                black_box(network.propagate_into(black_box(inputs), &mut buffers).ok());
+            }
+        })
+    });
+
+    let batch = NetworkBatch::new(&networks.iter().collect::<Vec<_>>()).unwrap();
+
+    c.bench_function("propagate_many/batch", |b| {
+        b.iter(|| {
+            batch
+                .propagate_into(black_box(&inputs), &mut buffers)
+                .map(|outputs| outputs[0])
+        })
+    });
+}
+
+criterion_group!(benches, propagate, propagate_into, propagate_many);
+criterion_main!(benches);
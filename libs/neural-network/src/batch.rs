@@ -0,0 +1,291 @@
+use crate::*;
+
+/// Number of networks processed together by the innermost loops; chosen so
+/// that the compiler can turn them into SIMD instructions on common targets.
+const LANES: usize = 8;
+
+/// Several networks of the same topology, laid out structure-of-arrays so
+/// that all of them can be propagated in a single pass.
+///
+/// Outputs are bit-identical to calling [`Network::propagate()`] on each of
+/// the networks separately.
+#[derive(Clone, Debug, PartialEq)]
+pub struct NetworkBatch {
+    len: usize,
+    layers: Vec<BatchLayer>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct BatchLayer {
+    input_size: usize,
+    output_size: usize,
+
+    /// Element `[(row * input_size + col) * len + network]` is the weight
+    /// connecting input `col` to neuron `row` in given network
+    weights: Vec<f32>,
+
+    /// Element `[row * len + network]` is the bias of neuron `row` in given
+    /// network
+    biases: Vec<f32>,
+
+    activation: Activation,
+}
+
+impl NetworkBatch {
+    pub fn new(networks: &[&Network]) -> Result<Self> {
+        let len = networks.len();
+
+        let Some(first) = networks.first() else {
+            return Ok(Self {
+                len,
+                layers: Vec::new(),
+            });
+        };
+
+        if let Some(idx) = networks
+            .iter()
+            .position(|network| network.topology() != first.topology())
+        {
+            return Err(Error::MismatchedTopology { network: idx });
+        }
+
+        let layers = first
+            .layers
+            .iter()
+            .enumerate()
+            .map(|(layer_idx, layer)| {
+                let input_size = layer.input_size;
+                let output_size = layer.output_size();
+
+                let mut weights = vec![0.0; input_size * output_size * len];
+                let mut biases = vec![0.0; output_size * len];
+
+                for (network_idx, network) in networks.iter().enumerate() {
+                    let layer = &network.layers[layer_idx];
+
+                    for (weight_idx, weight) in layer.weights.iter().enumerate() {
+                        weights[weight_idx * len + network_idx] = *weight;
+                    }
+
+                    for (row, bias) in layer.biases.iter().enumerate() {
+                        biases[row * len + network_idx] = *bias;
+                    }
+                }
+
+                BatchLayer {
+                    input_size,
+                    output_size,
+                    weights,
+                    biases,
+                    activation: layer.activation,
+                }
+            })
+            .collect();
+
+        Ok(Self { len, layers })
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Propagates each network through its own inputs.
+    ///
+    /// `inputs` contain input vectors of all the networks one after another
+    /// (i.e. `inputs[network * input_size + input]`) and outputs are returned
+    /// in the same manner.
+    pub fn propagate_into<'a>(
+        &self,
+        inputs: &[f32],
+        buffers: &'a mut Buffers,
+    ) -> Result<&'a [f32]> {
+        let Buffers { output, scratch } = buffers;
+
+        let input_size = self.layers.first().map_or(0, |layer| layer.input_size);
+
+        if inputs.len() != input_size * self.len {
+            return Err(Error::MismatchedInputSize {
+                layer: 0,
+                got: inputs.len(),
+                expected: input_size * self.len,
+            });
+        }
+
+        transpose(inputs, self.len, input_size, scratch);
+
+        for layer in &self.layers {
+            layer.propagate_into(self.len, scratch, output);
+
+            std::mem::swap(output, scratch);
+        }
+
+        let output_size = self
+            .layers
+            .last()
+            .map_or(input_size, |layer| layer.output_size);
+
+        transpose(scratch, output_size, self.len, output);
+
+        Ok(output)
+    }
+}
+
+impl BatchLayer {
+    fn propagate_into(&self, len: usize, inputs: &[f32], outputs: &mut Vec<f32>) {
+        outputs.clear();
+
+        // Same starting point as `f32::sum()`, so that results match
+        // `Network::propagate()` bit-for-bit
+        outputs.resize(self.output_size * len, -0.0);
+
+        for (row, outputs) in outputs.chunks_exact_mut(len.max(1)).enumerate() {
+            for col in 0..self.input_size {
+                let inputs = &inputs[col * len..][..len];
+                let weights = &self.weights[(row * self.input_size + col) * len..][..len];
+
+                mul_add(outputs, inputs, weights);
+            }
+
+            let biases = &self.biases[row * len..][..len];
+
+            for (output, bias) in outputs.iter_mut().zip(biases) {
+                *output = self.activation.apply(bias + *output);
+            }
+        }
+    }
+}
+
+/// Performs `acc[i] += lhs[i] * rhs[i]` in chunks of [`LANES`].
+fn mul_add(acc: &mut [f32], lhs: &[f32], rhs: &[f32]) {
+    let mut acc_chunks = acc.chunks_exact_mut(LANES);
+    let mut lhs_chunks = lhs.chunks_exact(LANES);
+    let mut rhs_chunks = rhs.chunks_exact(LANES);
+
+    for ((acc, lhs), rhs) in (&mut acc_chunks).zip(&mut lhs_chunks).zip(&mut rhs_chunks) {
+        for lane in 0..LANES {
+            acc[lane] += lhs[lane] * rhs[lane];
+        }
+    }
+
+    let acc = acc_chunks.into_remainder();
+
+    for ((acc, lhs), rhs) in acc
+        .iter_mut()
+        .zip(lhs_chunks.remainder())
+        .zip(rhs_chunks.remainder())
+    {
+        *acc += lhs * rhs;
+    }
+}
+
+/// Transposes row-major `rows × cols` matrix into `dst`.
+fn transpose(src: &[f32], rows: usize, cols: usize, dst: &mut Vec<f32>) {
+    dst.clear();
+    dst.resize(rows * cols, 0.0);
+
+    for row in 0..rows {
+        for col in 0..cols {
+            dst[col * rows + row] = src[row * cols + col];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn networks(count: usize) -> Vec<Network> {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+        (0..count)
+            .map(|_| {
+                Network::random(
+                    &mut rng,
+                    &[
+                        LayerTopology::new(3),
+                        LayerTopology::new(6),
+                        LayerTopology {
+                            neurons: 2,
+                            activation: Activation::Tanh,
+                        },
+                    ],
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn matches_individual_propagation() {
+        // 11 = one full chunk of lanes + remainder
+        let networks = networks(11);
+        let batch = NetworkBatch::new(&networks.iter().collect::<Vec<_>>()).unwrap();
+
+        let inputs: Vec<f32> = (0..networks.len() * 3)
+            .map(|i| (i as f32 * 0.37).sin())
+            .collect();
+
+        let expected: Vec<f32> = networks
+            .iter()
+            .zip(inputs.chunks_exact(3))
+            .flat_map(|(network, inputs)| network.propagate(inputs.to_vec()))
+            .collect();
+
+        let mut buffers = Buffers::default();
+
+        for _ in 0..2 {
+            let actual = batch.propagate_into(&inputs, &mut buffers).unwrap();
+
+            assert_eq!(actual, expected.as_slice());
+        }
+    }
+
+    #[test]
+    fn rejects_mismatched_topologies() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut networks = networks(2);
+
+        networks.push(Network::random(
+            &mut rng,
+            &[LayerTopology::new(3), LayerTopology::new(2)],
+        ));
+
+        assert!(matches!(
+            NetworkBatch::new(&networks.iter().collect::<Vec<_>>()),
+            Err(Error::MismatchedTopology { network: 2 })
+        ));
+    }
+
+    #[test]
+    fn rejects_mismatched_inputs() {
+        let networks = networks(2);
+        let batch = NetworkBatch::new(&networks.iter().collect::<Vec<_>>()).unwrap();
+
+        assert!(matches!(
+            batch.propagate_into(&[0.0; 5], &mut Buffers::default()),
+            Err(Error::MismatchedInputSize {
+                layer: 0,
+                got: 5,
+                expected: 6
+            })
+        ));
+    }
+
+    #[test]
+    fn empty() {
+        let batch = NetworkBatch::new(&[]).unwrap();
+
+        assert!(batch.is_empty());
+        assert!(
+            batch
+                .propagate_into(&[], &mut Buffers::default())
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -1,27 +1,97 @@
+mod batch;
+
+pub use self::batch::*;
 use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
 use std::iter::once;
 use thiserror::Error;
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// Version of the format produced by `Network::to_json()` / `to_bytes()`.
+///
+/// Version 1 (with a single, network-wide activation) is still accepted when
+/// loading.
+pub const FORMAT_VERSION: u32 = 2;
+
 #[derive(Debug, Error)]
 pub enum Error {
-    #[error("got {got} inputs, but {expected} inputs were expected")]
-    MismatchedInputSize { got: usize, expected: usize },
+    #[error("layer {layer} got {got} inputs, but {expected} inputs were expected")]
+    MismatchedInputSize {
+        layer: usize,
+        got: usize,
+        expected: usize,
+    },
+
+    #[error("got {got} weights, but {expected} weights were expected")]
+    WeightCountMismatch { got: usize, expected: usize },
+
+    #[error("topology must contain at least two layers")]
+    EmptyTopology,
+
+    #[error("network {network} has a different topology than the first one")]
+    MismatchedTopology { network: usize },
+
+    #[error("got format version {got}, but version {expected} was expected")]
+    UnsupportedVersion { got: u32, expected: u32 },
+
+    #[error("couldn't (de)serialize JSON network: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("couldn't (de)serialize binary network: {0}")]
+    Binary(#[from] postcard::Error),
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct LayerTopology {
     pub neurons: usize,
+
+    /// Function applied to outputs of this layer's neurons; ignored for the
+    /// input layer.
+    pub activation: Activation,
 }
 
 impl LayerTopology {
     pub fn new(neurons: usize) -> Self {
-        Self { neurons }
+        Self {
+            neurons,
+            activation: Activation::default(),
+        }
     }
 }
 
-#[derive(Debug)]
+/// Note that the binary format identifies variants by their position, so new
+/// ones must be added at the end.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Activation {
+    #[default]
+    Relu,
+
+    /// ReLU with a slope of 0.01 for negative values
+    LeakyRelu,
+
+    Sigmoid,
+    Tanh,
+    Identity,
+    Softsign,
+}
+
+impl Activation {
+    pub fn apply(self, value: f32) -> f32 {
+        match self {
+            Self::Relu => relu(value),
+            Self::LeakyRelu => value.max(0.01 * value),
+            Self::Sigmoid => 1.0 / (1.0 + libm::expf(-value)),
+            Self::Tanh => libm::tanhf(value),
+            Self::Identity => value,
+            Self::Softsign => value / (1.0 + value.abs()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "NetworkRepr", into = "NetworkRepr")]
 pub struct Network {
     layers: Vec<Layer>,
 }
@@ -30,123 +100,348 @@ impl Network {
     pub fn random(rng: &mut dyn RngCore, layers: &[LayerTopology]) -> Self {
         let layers = layers
             .windows(2)
-            .map(|layers| Layer::random(rng, layers[0].neurons, layers[1].neurons))
+            .map(|layers| {
+                Layer::random(
+                    rng,
+                    layers[0].neurons,
+                    layers[1].neurons,
+                    layers[1].activation,
+                )
+            })
             .collect();
 
         Self { layers }
     }
 
     pub fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
-        self.layers
-            .iter()
-            .fold(inputs, |inputs, layer| layer.propagate(inputs))
+        self.try_propagate(inputs)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    pub fn try_propagate(&self, inputs: Vec<f32>) -> Result<Vec<f32>> {
+        let mut buffers = Buffers::default();
+
+        self.propagate_into(&inputs, &mut buffers)?;
+
+        Ok(buffers.output)
+    }
+
+    /// Allocation-free version of `try_propagate()` - once `buffers` have
+    /// grown to fit the widest layer, they are reused for all the subsequent
+    /// calls.
+    pub fn propagate_into<'a>(
+        &self,
+        inputs: &[f32],
+        buffers: &'a mut Buffers,
+    ) -> Result<&'a [f32]> {
+        let Buffers { output, scratch } = buffers;
+
+        output.clear();
+        output.extend_from_slice(inputs);
+
+        for (idx, layer) in self.layers.iter().enumerate() {
+            if output.len() != layer.input_size {
+                return Err(Error::MismatchedInputSize {
+                    layer: idx,
+                    got: output.len(),
+                    expected: layer.input_size,
+                });
+            }
+
+            layer.propagate_into(output, scratch);
+
+            std::mem::swap(output, scratch);
+        }
+
+        Ok(output)
     }
 
     pub fn weights(&self) -> impl Iterator<Item = f32> + '_ {
-        self.layers
-            .iter()
-            .flat_map(|layer| layer.neurons.iter())
-            .flat_map(|neuron| once(&neuron.bias).chain(&neuron.weights))
-            .copied()
+        self.layers.iter().flat_map(|layer| layer.weights())
     }
 
     pub fn from_weights(layers: &[LayerTopology], weights: impl IntoIterator<Item = f32>) -> Self {
-        assert!(layers.len() > 1);
+        Self::try_from_weights(layers, weights).unwrap_or_else(|err| panic!("{err}"))
+    }
 
-        let mut weights = weights.into_iter();
+    /// Returns how many weights (including biases) a network of given
+    /// topology has, i.e. how many `from_weights()` expects.
+    pub fn weight_count(layers: &[LayerTopology]) -> usize {
+        layers
+            .windows(2)
+            .map(|layers| Layer::weight_count(layers[0].neurons, layers[1].neurons))
+            .sum()
+    }
+
+    pub fn try_from_weights(
+        layers: &[LayerTopology],
+        weights: impl IntoIterator<Item = f32>,
+    ) -> Result<Self> {
+        if layers.len() < 2 {
+            return Err(Error::EmptyTopology);
+        }
+
+        let weights: Vec<_> = weights.into_iter().collect();
+
+        let expected = Self::weight_count(layers);
+
+        if weights.len() != expected {
+            return Err(Error::WeightCountMismatch {
+                got: weights.len(),
+                expected,
+            });
+        }
+
+        let mut weights = weights.as_slice();
 
         let layers = layers
             .windows(2)
-            .map(|layers| Layer::from_weights(layers[0].neurons, layers[1].neurons, &mut weights))
+            .map(|layers| {
+                let (layer, rest) =
+                    weights.split_at(Layer::weight_count(layers[0].neurons, layers[1].neurons));
+
+                weights = rest;
+
+                Layer::from_weights(layers[0].neurons, layers[1].activation, layer)
+            })
             .collect();
 
-        if weights.next().is_some() {
-            panic!("got too many weights");
+        Ok(Self { layers })
+    }
+
+    pub fn topology(&self) -> Vec<LayerTopology> {
+        let input = self.layers.first().map_or(0, |layer| layer.input_size);
+
+        once(LayerTopology::new(input))
+            .chain(self.layers.iter().map(|layer| LayerTopology {
+                neurons: layer.output_size(),
+                activation: layer.activation,
+            }))
+            .collect()
+    }
+
+    /// Serializes network into a self-describing JSON document containing
+    /// its topology (including activations) and weights.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(&NetworkRepr::from(self.clone()))?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let header: NetworkHeader = serde_json::from_str(json)?;
+
+        match header.version {
+            1 => serde_json::from_str::<NetworkReprV1>(json)?.try_into(),
+            _ => serde_json::from_str::<NetworkRepr>(json)?.try_into(),
         }
+    }
 
-        Self { layers }
+    /// Serializes network into the same structure as `to_json()`, but
+    /// encoded with a compact binary format (postcard).
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(postcard::to_allocvec(&NetworkRepr::from(self.clone()))?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        // `version` is the first field, so it can be read before we commit
+        // to decoding the rest of the buffer
+        let (version, _) = postcard::take_from_bytes::<u32>(bytes)?;
+
+        match version {
+            1 => postcard::from_bytes::<NetworkReprV1>(bytes)?.try_into(),
+            _ => postcard::from_bytes::<NetworkRepr>(bytes)?.try_into(),
+        }
     }
 }
 
-#[derive(Debug, Clone, PartialEq)]
-struct Layer {
-    neurons: Vec<Neuron>,
+/// On-disk representation of [`Network`].
+#[derive(Serialize, Deserialize)]
+struct NetworkRepr {
+    version: u32,
+    topology: Vec<LayerTopology>,
+    weights: Vec<f32>,
 }
 
-impl Layer {
-    pub fn random(rng: &mut dyn RngCore, input_size: usize, output_size: usize) -> Self {
-        let neurons = (0..output_size)
-            .map(|_| Neuron::random(rng, input_size))
-            .collect();
+/// On-disk representation of [`Network`] from before activations could be
+/// chosen per layer.
+#[derive(Deserialize)]
+struct NetworkReprV1 {
+    version: u32,
+    topology: Vec<LayerTopologyV1>,
+    activation: Activation,
+    weights: Vec<f32>,
+}
+
+#[derive(Deserialize)]
+struct LayerTopologyV1 {
+    neurons: usize,
+}
 
-        Self { neurons }
+#[derive(Deserialize)]
+struct NetworkHeader {
+    version: u32,
+}
+
+impl NetworkRepr {
+    fn check_version(version: u32) -> Result<()> {
+        if version != FORMAT_VERSION {
+            return Err(Error::UnsupportedVersion {
+                got: version,
+                expected: FORMAT_VERSION,
+            });
+        }
+
+        Ok(())
     }
+}
 
-    pub fn propagate(&self, inputs: Vec<f32>) -> Vec<f32> {
-        self.neurons
-            .iter()
-            .map(|neuron| {
-                neuron
-                    .propagate(&inputs)
-                    .expect("Error happened in neuron propagation")
-            })
-            .collect()
+impl From<Network> for NetworkRepr {
+    fn from(network: Network) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            topology: network.topology(),
+            weights: network.weights().collect(),
+        }
     }
+}
 
-    pub fn from_weights(
-        input_size: usize,
-        output_size: usize,
-        weights: &mut dyn Iterator<Item = f32>,
-    ) -> Self {
-        let neurons = (0..output_size)
-            .map(|_| Neuron::from_weights(input_size, weights))
+impl TryFrom<NetworkRepr> for Network {
+    type Error = Error;
+
+    fn try_from(repr: NetworkRepr) -> Result<Self> {
+        NetworkRepr::check_version(repr.version)?;
+
+        Self::try_from_weights(&repr.topology, repr.weights)
+    }
+}
+
+impl TryFrom<NetworkReprV1> for Network {
+    type Error = Error;
+
+    fn try_from(repr: NetworkReprV1) -> Result<Self> {
+        if repr.version != 1 {
+            return Err(Error::UnsupportedVersion {
+                got: repr.version,
+                expected: 1,
+            });
+        }
+
+        let topology: Vec<_> = repr
+            .topology
+            .into_iter()
+            .map(|layer| LayerTopology {
+                neurons: layer.neurons,
+                activation: repr.activation,
+            })
             .collect();
 
-        Self { neurons }
+        Self::try_from_weights(&topology, repr.weights)
     }
 }
 
+/// Scratch space for [`Network::propagate_into()`] and
+/// [`NetworkBatch::propagate_into()`].
+#[derive(Clone, Debug, Default)]
+pub struct Buffers {
+    output: Vec<f32>,
+    scratch: Vec<f32>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
-struct Neuron {
-    bias: f32,
+struct Layer {
+    input_size: usize,
+
+    /// Row-major `output_size × input_size` matrix - i.e. row `n` contains
+    /// weights of the `n`-th neuron
     weights: Vec<f32>,
+
+    biases: Vec<f32>,
+    activation: Activation,
 }
 
-impl Neuron {
-    pub fn random(rng: &mut dyn RngCore, input_size: usize) -> Self {
-        let bias = rng.random_range(-1.0..=1.0);
+impl Layer {
+    pub fn random(
+        rng: &mut dyn RngCore,
+        input_size: usize,
+        output_size: usize,
+        activation: Activation,
+    ) -> Self {
+        let mut weights = Vec::with_capacity(output_size * input_size);
+        let mut biases = Vec::with_capacity(output_size);
 
-        let weights = (0..input_size)
-            .map(|_| rng.random_range(-1.0..=1.0))
-            .collect();
+        // Drawn neuron-by-neuron (bias first), to keep the same order as
+        // `weights()` and `from_weights()`
+        for _ in 0..output_size {
+            biases.push(rng.random_range(-1.0..=1.0));
 
-        Self { bias, weights }
-    }
-    pub fn propagate(&self, inputs: &[f32]) -> Result<f32> {
-        if inputs.len() != self.weights.len() {
-            return Err(Error::MismatchedInputSize {
-                got: inputs.len(),
-                expected: self.weights.len(),
-            });
+            for _ in 0..input_size {
+                weights.push(rng.random_range(-1.0..=1.0));
+            }
+        }
+
+        Self {
+            input_size,
+            weights,
+            biases,
+            activation,
         }
+    }
+
+    fn propagate_into(&self, inputs: &[f32], outputs: &mut Vec<f32>) {
+        debug_assert_eq!(inputs.len(), self.input_size);
+
+        outputs.clear();
+
+        outputs.extend(self.biases.iter().zip(self.rows()).map(|(bias, row)| {
+            let output = inputs
+                .iter()
+                .zip(row)
+                .map(|(input, weight)| input * weight)
+                .sum::<f32>();
 
-        let output = inputs
+            self.activation.apply(bias + output)
+        }));
+    }
+
+    fn output_size(&self) -> usize {
+        self.biases.len()
+    }
+
+    fn rows(&self) -> impl Iterator<Item = &[f32]> {
+        (0..self.output_size()).map(|row| &self.weights[row * self.input_size..][..self.input_size])
+    }
+
+    /// Returns each neuron's bias followed by its weights.
+    fn weights(&self) -> impl Iterator<Item = f32> + '_ {
+        self.biases
             .iter()
-            .zip(&self.weights)
-            .map(|(input, weight)| input * weight)
-            .sum::<f32>();
+            .zip(self.rows())
+            .flat_map(|(bias, row)| once(bias).chain(row))
+            .copied()
+    }
 
-        Ok(relu(self.bias + output))
+    fn weight_count(input_size: usize, output_size: usize) -> usize {
+        (input_size + 1) * output_size
     }
 
-    fn from_weights(input_size: usize, weights: &mut dyn Iterator<Item = f32>) -> Self {
-        let bias = weights.next().expect("got not enough weights");
+    /// Builds layer out of exactly `weight_count(input_size, _)` weights,
+    /// ordered like `weights()` returns them.
+    fn from_weights(input_size: usize, activation: Activation, weights: &[f32]) -> Self {
+        let output_size = weights.len() / (input_size + 1);
 
-        let weights = (0..input_size)
-            .map(|_| weights.next().expect("got not enough weights"))
-            .collect();
+        let mut biases = Vec::with_capacity(output_size);
+        let mut matrix = Vec::with_capacity(output_size * input_size);
+
+        for neuron in weights.chunks_exact(input_size + 1) {
+            biases.push(neuron[0]);
+            matrix.extend_from_slice(&neuron[1..]);
+        }
 
-        Self { bias, weights }
+        Self {
+            input_size,
+            weights: matrix,
+            biases,
+            activation,
+        }
     }
 }
 
@@ -158,103 +453,86 @@ pub fn relu(value: f32) -> f32 {
 mod tests {
     use super::*;
     use approx::assert_relative_eq;
-    use rand::SeedableRng;
-    use rand_chacha::ChaCha8Rng;
 
-    mod neuron {
-        use super::*;
+    mod layer {
+        use approx::assert_relative_eq;
+        use rand::{Rng, SeedableRng};
+        use rand_chacha::ChaCha8Rng;
+
+        use crate::{Activation, Layer};
+
+        fn outputs(layer: &Layer, inputs: &[f32]) -> Vec<f32> {
+            let mut outputs = Vec::new();
+
+            layer.propagate_into(inputs, &mut outputs);
+
+            outputs
+        }
 
         #[test]
         fn random_generation() {
             let mut rng = ChaCha8Rng::from_seed(Default::default());
-            let neuron = Neuron::random(&mut rng, 4);
+            let layer = Layer::random(&mut rng, 4, 1, Activation::Relu);
 
-            assert_relative_eq!(neuron.bias, -0.6255188);
+            assert_relative_eq!(layer.biases.as_slice(), [-0.6255188].as_ref());
             assert_relative_eq!(
-                neuron.weights.as_slice(),
+                layer.weights.as_slice(),
                 [0.67383933, 0.81812596, 0.26284885, 0.5238805].as_ref()
             );
         }
 
         #[test]
-        fn propagate() {
-            let neuron = Neuron {
-                bias: 0.5,
-                weights: vec![-0.3, 0.8],
-            };
+        fn random() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let layer = Layer::random(&mut rng, 3, 2, Activation::Relu);
 
-            assert_relative_eq!(neuron.propagate(&[-10.0, -10.0]).unwrap(), 0.0,);
+            // Each neuron draws its bias first, followed by its weights
+            let mut rng2 = ChaCha8Rng::from_seed(Default::default());
+            let expected: Vec<f32> = (0..8).map(|_| rng2.random_range(-1.0..=1.0)).collect();
 
+            assert_eq!(layer.output_size(), 2);
             assert_relative_eq!(
-                neuron.propagate(&[0.5, 1.0]).unwrap(),
-                (-0.3 * 0.5) + (0.8 * 1.0) + 0.5
-            )
-        }
-    }
-
-    mod layer {
-        use approx::assert_relative_eq;
-        use rand::SeedableRng;
-        use rand_chacha::ChaCha8Rng;
-
-        use crate::{Layer, Neuron};
-
-        impl approx::AbsDiffEq for Neuron {
-            type Epsilon = f32;
-
-            fn default_epsilon() -> f32 {
-                f32::EPSILON
-            }
-
-            fn abs_diff_eq(&self, other: &Self, epsilon: f32) -> bool {
-                self.bias.abs_diff_eq(&other.bias, epsilon)
-                    && self.weights.len() == other.weights.len()
-                    && self
-                        .weights
-                        .iter()
-                        .zip(other.weights.iter())
-                        .all(|(a, b)| a.abs_diff_eq(b, epsilon))
-            }
-        }
-
-        impl approx::RelativeEq for Neuron {
-            fn default_max_relative() -> f32 {
-                f32::default_max_relative()
-            }
-
-            fn relative_eq(&self, other: &Self, epsilon: f32, max_relative: f32) -> bool {
-                self.bias.relative_eq(&other.bias, epsilon, max_relative)
-                    && self.weights.len() == other.weights.len()
-                    && self
-                        .weights
-                        .iter()
-                        .zip(other.weights.iter())
-                        .all(|(a, b)| a.relative_eq(b, epsilon, max_relative))
-            }
+                layer.weights().collect::<Vec<_>>().as_slice(),
+                expected.as_slice()
+            );
         }
 
         #[test]
-        fn random() {
-            let mut rng = ChaCha8Rng::from_seed(Default::default());
-            let layer = Layer::random(&mut rng, 3, 2);
+        fn propagate_single_neuron() {
+            let layer = Layer {
+                input_size: 2,
+                weights: vec![-0.3, 0.8],
+                biases: vec![0.5],
+                activation: Activation::Relu,
+            };
 
-            let mut rng2 = ChaCha8Rng::from_seed(Default::default());
-            let expected_neurons = vec![Neuron::random(&mut rng2, 3), Neuron::random(&mut rng2, 3)];
+            assert_relative_eq!(outputs(&layer, &[-10.0, -10.0]).as_slice(), [0.0].as_ref());
 
-            assert_eq!(layer.neurons.len(), 2);
-            assert_relative_eq!(layer.neurons.as_slice(), expected_neurons.as_slice());
+            assert_relative_eq!(
+                outputs(&layer, &[0.5, 1.0]).as_slice(),
+                [(-0.3 * 0.5) + (0.8 * 1.0) + 0.5].as_ref()
+            )
         }
 
         #[test]
         fn propagate() {
             let mut rng = ChaCha8Rng::from_seed(Default::default());
-            let layer = Layer::random(&mut rng, 3, 2);
+            let layer = Layer::random(&mut rng, 3, 2, Activation::Relu);
 
             assert_relative_eq!(
-                layer.propagate(vec![-10.0, -10.0, 5.0]).as_slice(),
+                outputs(&layer, &[-10.0, -10.0, 5.0]).as_slice(),
                 vec![0.0, 1.3577781].as_slice()
             );
         }
+
+        #[test]
+        fn from_weights() {
+            let layer = Layer::from_weights(2, Activation::Relu, &[0.1, 0.2, 0.3, 0.4, 0.5, 0.6]);
+
+            assert_eq!(layer.input_size, 2);
+            assert_relative_eq!(layer.biases.as_slice(), [0.1, 0.4].as_ref());
+            assert_relative_eq!(layer.weights.as_slice(), [0.2, 0.3, 0.5, 0.6].as_ref());
+        }
     }
 
     mod network {
@@ -262,7 +540,7 @@ mod tests {
         use rand::SeedableRng;
         use rand_chacha::ChaCha8Rng;
 
-        use crate::{Layer, LayerTopology, Network, Neuron};
+        use crate::{Activation, Buffers, Error, Layer, LayerTopology, Network};
 
         impl approx::AbsDiffEq for Layer {
             type Epsilon = f32;
@@ -272,12 +550,10 @@ mod tests {
             }
 
             fn abs_diff_eq(&self, other: &Self, epsilon: f32) -> bool {
-                self.neurons.len() == other.neurons.len()
-                    && self
-                        .neurons
-                        .iter()
-                        .zip(other.neurons.iter())
-                        .all(|(a, b)| a.abs_diff_eq(b, epsilon))
+                self.activation == other.activation
+                    && self.input_size == other.input_size
+                    && self.biases.abs_diff_eq(&other.biases, epsilon)
+                    && self.weights.abs_diff_eq(&other.weights, epsilon)
             }
         }
 
@@ -287,12 +563,14 @@ mod tests {
             }
 
             fn relative_eq(&self, other: &Self, epsilon: f32, max_relative: f32) -> bool {
-                self.neurons.len() == other.neurons.len()
+                self.activation == other.activation
+                    && self.input_size == other.input_size
                     && self
-                        .neurons
-                        .iter()
-                        .zip(other.neurons.iter())
-                        .all(|(a, b)| a.relative_eq(b, epsilon, max_relative))
+                        .biases
+                        .relative_eq(&other.biases, epsilon, max_relative)
+                    && self
+                        .weights
+                        .relative_eq(&other.weights, epsilon, max_relative)
             }
         }
 
@@ -302,9 +580,9 @@ mod tests {
             let network = Network::random(
                 &mut rng,
                 &[
-                    LayerTopology { neurons: 3 },
-                    LayerTopology { neurons: 4 },
-                    LayerTopology { neurons: 5 },
+                    LayerTopology::new(3),
+                    LayerTopology::new(4),
+                    LayerTopology::new(5),
                 ],
             );
 
@@ -313,8 +591,8 @@ mod tests {
             assert_relative_eq!(
                 network.layers.as_slice(),
                 [
-                    Layer::random(&mut rng2, 3, 4),
-                    Layer::random(&mut rng2, 4, 5)
+                    Layer::random(&mut rng2, 3, 4, Activation::Relu),
+                    Layer::random(&mut rng2, 4, 5, Activation::Relu)
                 ]
                 .as_ref()
             );
@@ -326,9 +604,9 @@ mod tests {
             let network = Network::random(
                 &mut rng,
                 &[
-                    LayerTopology { neurons: 3 },
-                    LayerTopology { neurons: 4 },
-                    LayerTopology { neurons: 5 },
+                    LayerTopology::new(3),
+                    LayerTopology::new(4),
+                    LayerTopology::new(5),
                 ],
             );
 
@@ -343,16 +621,16 @@ mod tests {
             let network = Network {
                 layers: vec![
                     Layer {
-                        neurons: vec![Neuron {
-                            bias: 0.1,
-                            weights: vec![0.2, 0.3, 0.4],
-                        }],
+                        input_size: 3,
+                        weights: vec![0.2, 0.3, 0.4],
+                        biases: vec![0.1],
+                        activation: Activation::Relu,
                     },
                     Layer {
-                        neurons: vec![Neuron {
-                            bias: 0.5,
-                            weights: vec![0.6, 0.7, 0.8],
-                        }],
+                        input_size: 3,
+                        weights: vec![0.6, 0.7, 0.8],
+                        biases: vec![0.5],
+                        activation: Activation::Relu,
                     },
                 ],
             };
@@ -365,7 +643,7 @@ mod tests {
 
         #[test]
         fn from_weights() {
-            let layers = &[LayerTopology { neurons: 3 }, LayerTopology { neurons: 2 }];
+            let layers = &[LayerTopology::new(3), LayerTopology::new(2)];
 
             let weights = vec![0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8];
             let network = Network::from_weights(layers, weights.clone());
@@ -373,5 +651,251 @@ mod tests {
 
             assert_relative_eq!(actual.as_slice(), weights.as_slice());
         }
+
+        #[test]
+        fn try_from_weights() {
+            let layers = &[LayerTopology::new(3), LayerTopology::new(2)];
+
+            assert!(matches!(
+                Network::try_from_weights(&layers[..1], []),
+                Err(Error::EmptyTopology)
+            ));
+
+            assert!(matches!(
+                Network::try_from_weights(layers, vec![0.1; 7]),
+                Err(Error::WeightCountMismatch {
+                    got: 7,
+                    expected: 8
+                })
+            ));
+
+            assert!(matches!(
+                Network::try_from_weights(layers, vec![0.1; 9]),
+                Err(Error::WeightCountMismatch {
+                    got: 9,
+                    expected: 8
+                })
+            ));
+
+            assert!(Network::try_from_weights(layers, vec![0.1; 8]).is_ok());
+        }
+
+        #[test]
+        fn try_propagate() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network =
+                Network::random(&mut rng, &[LayerTopology::new(3), LayerTopology::new(2)]);
+
+            assert!(matches!(
+                network.try_propagate(vec![1.0, 2.0]),
+                Err(Error::MismatchedInputSize {
+                    layer: 0,
+                    got: 2,
+                    expected: 3
+                })
+            ));
+
+            assert_relative_eq!(
+                network
+                    .try_propagate(vec![-10.0, -10.0, 5.0])
+                    .unwrap()
+                    .as_slice(),
+                network.propagate(vec![-10.0, -10.0, 5.0]).as_slice()
+            );
+        }
+
+        #[test]
+        fn propagate_into() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let network = Network::random(
+                &mut rng,
+                &[
+                    LayerTopology::new(3),
+                    LayerTopology::new(4),
+                    LayerTopology::new(5),
+                ],
+            );
+
+            let mut buffers = Buffers::default();
+
+            for _ in 0..2 {
+                assert_relative_eq!(
+                    network
+                        .propagate_into(&[-10.0, -10.0, 5.0], &mut buffers)
+                        .unwrap(),
+                    [0.0, 0.0, 2.2639842, 1.7549752, 2.1092079].as_ref()
+                );
+            }
+
+            assert!(matches!(
+                network.propagate_into(&[1.0], &mut buffers),
+                Err(Error::MismatchedInputSize {
+                    layer: 0,
+                    got: 1,
+                    expected: 3
+                })
+            ));
+        }
+
+        #[test]
+        fn topology() {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+            let layers = [
+                LayerTopology::new(3),
+                LayerTopology::new(4),
+                LayerTopology::new(5),
+            ];
+
+            let network = Network::random(&mut rng, &layers);
+
+            assert_eq!(network.topology(), layers);
+        }
+    }
+
+    mod activation {
+        use super::*;
+
+        #[test]
+        fn apply() {
+            let cases = [
+                (Activation::Relu, [0.0, 0.0, 2.0]),
+                (Activation::LeakyRelu, [-0.02, 0.0, 2.0]),
+                (Activation::Sigmoid, [0.11920292, 0.5, 0.8807971]),
+                (Activation::Tanh, [-0.9640276, 0.0, 0.9640276]),
+                (Activation::Identity, [-2.0, 0.0, 2.0]),
+                (Activation::Softsign, [-0.6666667, 0.0, 0.6666667]),
+            ];
+
+            for (activation, expected) in cases {
+                let actual = [-2.0, 0.0, 2.0].map(|value| activation.apply(value));
+
+                assert_relative_eq!(actual.as_ref(), expected.as_ref());
+            }
+        }
+
+        #[test]
+        fn propagate_uses_per_layer_activations() {
+            let network = Network::from_weights(
+                &[
+                    LayerTopology::new(1),
+                    LayerTopology::new(1),
+                    LayerTopology {
+                        neurons: 1,
+                        activation: Activation::Identity,
+                    },
+                ],
+                [0.0, 1.0, 0.0, -1.0],
+            );
+
+            assert_relative_eq!(network.propagate(vec![3.0]).as_slice(), [-3.0].as_ref());
+            assert_relative_eq!(network.propagate(vec![-3.0]).as_slice(), [0.0].as_ref());
+        }
+    }
+
+    mod serialization {
+        use rand::SeedableRng;
+        use rand_chacha::ChaCha8Rng;
+
+        use crate::*;
+
+        fn network() -> Network {
+            let mut rng = ChaCha8Rng::from_seed(Default::default());
+
+            Network::random(
+                &mut rng,
+                &[
+                    LayerTopology::new(3),
+                    LayerTopology::new(4),
+                    LayerTopology::new(2),
+                ],
+            )
+        }
+
+        #[test]
+        fn json_roundtrip() {
+            let network = network();
+            let json = network.to_json().unwrap();
+
+            assert_eq!(Network::from_json(&json).unwrap(), network);
+        }
+
+        #[test]
+        fn json_is_self_describing() {
+            let json = Network::from_weights(
+                &[
+                    LayerTopology::new(1),
+                    LayerTopology {
+                        neurons: 1,
+                        activation: Activation::Tanh,
+                    },
+                ],
+                [0.5, -0.25],
+            )
+            .to_json()
+            .unwrap();
+
+            assert_eq!(
+                json,
+                r#"{"version":2,"topology":[{"neurons":1,"activation":"relu"},{"neurons":1,"activation":"tanh"}],"weights":[0.5,-0.25]}"#
+            );
+        }
+
+        #[test]
+        fn loads_v1_json() {
+            let json = r#"{"version":1,"topology":[{"neurons":1},{"neurons":1}],"activation":"relu","weights":[0.5,-0.25]}"#;
+            let network = Network::from_json(json).unwrap();
+
+            assert_eq!(
+                network.topology(),
+                [LayerTopology::new(1), LayerTopology::new(1)]
+            );
+
+            assert_eq!(network.weights().collect::<Vec<_>>(), [0.5, -0.25]);
+        }
+
+        #[test]
+        fn bytes_roundtrip() {
+            let network = network();
+            let bytes = network.to_bytes().unwrap();
+
+            assert_eq!(Network::from_bytes(&bytes).unwrap(), network);
+        }
+
+        #[test]
+        fn rejects_unsupported_version() {
+            let json = r#"{"version":99,"topology":[],"activation":"relu","weights":[]}"#;
+
+            assert!(matches!(
+                Network::from_json(json),
+                Err(Error::UnsupportedVersion {
+                    got: 99,
+                    expected: FORMAT_VERSION
+                })
+            ));
+        }
+
+        #[test]
+        fn rejects_empty_topology() {
+            let json =
+                r#"{"version":1,"topology":[{"neurons":1}],"activation":"relu","weights":[]}"#;
+
+            assert!(matches!(
+                Network::from_json(json),
+                Err(Error::EmptyTopology)
+            ));
+        }
+
+        #[test]
+        fn rejects_mismatched_weights() {
+            let json = r#"{"version":1,"topology":[{"neurons":1},{"neurons":1}],"activation":"relu","weights":[0.5]}"#;
+
+            assert!(matches!(
+                Network::from_json(json),
+                Err(Error::WeightCountMismatch {
+                    got: 1,
+                    expected: 2
+                })
+            ));
+        }
     }
 }
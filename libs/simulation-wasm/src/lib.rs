@@ -1,40 +1,232 @@
 mod utils;
+mod world_view;
 
+pub use self::world_view::{ANIMAL_STRIDE, FOOD_STRIDE, PREDATOR_STRIDE};
+
+use lib_genetic_algorithm as ga;
 use lib_simulation::{self as sim};
 use rand::{prelude::*, rng};
+use rand_chacha::ChaCha8Rng;
 use wasm_bindgen::prelude::*;
+use world_view::WorldView;
 
 #[wasm_bindgen]
 pub struct Simulation {
-    rng: ThreadRng,
+    rng: ChaCha8Rng,
     sim: sim::Simulation,
+    view: WorldView,
+}
+
+impl Simulation {
+    fn from_sim(sim: sim::Simulation, rng: ChaCha8Rng) -> Self {
+        let mut view = WorldView::default();
+
+        view.refresh(sim.world());
+
+        Self { rng, sim, view }
+    }
 }
 
 #[wasm_bindgen]
 impl Simulation {
+    /// Creates a randomly seeded simulation.
+    ///
+    /// `config` is an object with fields of `lib_simulation::Config` (named
+    /// the same, e.g. `world_animals`); missing fields - or the entire
+    /// config - keep their default values.
     #[wasm_bindgen(constructor)]
-    pub fn new() -> Self {
-        let mut rng = rng();
-        let sim = sim::Simulation::random(&mut rng);
+    pub fn new(config: JsValue) -> Result<Simulation, JsError> {
+        Self::from_seed(rng().random(), config)
+    }
+
+    #[wasm_bindgen(js_name = fromSeed)]
+    pub fn from_seed(seed: u64, config: JsValue) -> Result<Simulation, JsError> {
+        utils::set_panic_hook();
+
+        let config = if config.is_undefined() || config.is_null() {
+            sim::Config::default()
+        } else {
+            serde_wasm_bindgen::from_value(config)?
+        };
+
+        config.validate()?;
+
+        let (sim, rng) = sim::Simulation::from_seed(config, seed);
+
+        Ok(Self::from_sim(sim, rng))
+    }
 
-        Self { rng, sim }
+    /// Serializes the whole simulation (including its generator, but only
+    /// the latest generation's statistics) into a compact binary snapshot
+    /// that can be passed to `importSnapshot()`.
+    #[wasm_bindgen(js_name = exportSnapshot)]
+    pub fn export_snapshot(&self) -> Result<Vec<u8>, JsError> {
+        Ok(self.sim.snapshot(&self.rng).to_bytes()?)
+    }
+
+    #[wasm_bindgen(js_name = importSnapshot)]
+    pub fn import_snapshot(bytes: &[u8]) -> Result<Simulation, JsError> {
+        utils::set_panic_hook();
+
+        let snapshot = sim::Snapshot::from_bytes(bytes)?;
+        let (sim, rng) = sim::Simulation::restore(snapshot)?;
+
+        Ok(Self::from_sim(sim, rng))
+    }
+
+    /// Config of this simulation, as accepted by `new()` and `fromSeed()`.
+    pub fn config(&self) -> Result<JsValue, JsError> {
+        Ok(serde_wasm_bindgen::to_value(self.sim.config())?)
     }
 
     pub fn world(&self) -> World {
         World::from(self.sim.world())
     }
 
-    pub fn step(&mut self) {
-        self.sim.step(&mut self.rng);
+    /// State of all the animals, packed into `animalStride` floats per
+    /// animal: x, y, rotation, speed, satiation, energy and whether it's
+    /// alive (1.0) or has starved (0.0).
+    ///
+    /// Unlike `world()`, this doesn't copy anything - the array is a view
+    /// into wasm memory, updated in place by each `step()` and `train()`.
+    /// Since it gets detached whenever wasm memory grows, it should be
+    /// re-acquired on each frame instead of being kept around.
+    #[wasm_bindgen(js_name = animalsView)]
+    pub fn animals_view(&self) -> js_sys::Float32Array {
+        // Safety: the view is only read by JS until the next call into
+        // wasm, and the buffer never shrinks or moves in between
+        unsafe { js_sys::Float32Array::view(&self.view.animals) }
+    }
+
+    /// State of all the foods, packed into `foodStride` floats per food: x
+    /// and y; see `animalsView()`.
+    #[wasm_bindgen(js_name = foodsView)]
+    pub fn foods_view(&self) -> js_sys::Float32Array {
+        // Safety: see `animals_view()`
+        unsafe { js_sys::Float32Array::view(&self.view.foods) }
+    }
+
+    /// State of all the predators, packed into `predatorStride` floats per
+    /// predator: x, y, rotation and the number of birds caught; see
+    /// `animalsView()`.
+    #[wasm_bindgen(js_name = predatorsView)]
+    pub fn predators_view(&self) -> js_sys::Float32Array {
+        // Safety: see `animals_view()`
+        unsafe { js_sys::Float32Array::view(&self.view.predators) }
+    }
+
+    #[wasm_bindgen(getter, js_name = animalStride)]
+    pub fn animal_stride(&self) -> usize {
+        ANIMAL_STRIDE
+    }
+
+    #[wasm_bindgen(getter, js_name = foodStride)]
+    pub fn food_stride(&self) -> usize {
+        FOOD_STRIDE
     }
 
-    pub fn train(&mut self) -> String {
+    #[wasm_bindgen(getter, js_name = predatorStride)]
+    pub fn predator_stride(&self) -> usize {
+        PREDATOR_STRIDE
+    }
+
+    /// Number of generations evolved so far.
+    #[wasm_bindgen(getter)]
+    pub fn generation(&self) -> usize {
+        self.sim.generation()
+    }
+
+    /// Number of steps performed within the current generation.
+    #[wasm_bindgen(getter)]
+    pub fn age(&self) -> usize {
+        self.sim.age()
+    }
+
+    /// Performs a single step; returns statistics of the generation that
+    /// has just finished (and got evolved), if any.
+    pub fn step(&mut self) -> Option<Statistics> {
+        let stats = self.sim.step(&mut self.rng);
+
+        self.view.refresh(self.sim.world());
+
+        stats.map(|stats| Statistics::from(&stats))
+    }
+
+    /// Steps until the current generation finishes.
+    pub fn train(&mut self) -> Statistics {
         let stats = self.sim.train(&mut self.rng);
 
-        format!(
-            "min={:.2}, max={:.2}, avg={:.2}",
-            stats.min_fitness, stats.max_fitness, stats.avg_fitness
-        )
+        self.view.refresh(self.sim.world());
+
+        Statistics::from(&stats)
+    }
+}
+
+impl Default for Simulation {
+    fn default() -> Self {
+        let (sim, rng) = sim::Simulation::from_seed(Default::default(), rng().random());
+
+        Self::from_sim(sim, rng)
+    }
+}
+
+/// Summary of a generation, as evaluated right before it got evolved; see
+/// `lib_genetic_algorithm::Statistics`.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct Statistics {
+    /// Number of generations evolved before this one
+    pub generation: usize,
+
+    #[wasm_bindgen(js_name = minFitness)]
+    pub min_fitness: f32,
+    #[wasm_bindgen(js_name = maxFitness)]
+    pub max_fitness: f32,
+    #[wasm_bindgen(js_name = avgFitness)]
+    pub avg_fitness: f32,
+    #[wasm_bindgen(js_name = medianFitness)]
+    pub median_fitness: f32,
+    #[wasm_bindgen(js_name = stdDevFitness)]
+    pub std_dev_fitness: f32,
+    #[wasm_bindgen(js_name = p10Fitness)]
+    pub p10_fitness: f32,
+    #[wasm_bindgen(js_name = p90Fitness)]
+    pub p90_fitness: f32,
+
+    #[wasm_bindgen(js_name = meanDistance)]
+    pub mean_distance: f32,
+
+    #[wasm_bindgen(js_name = bestIndex)]
+    pub best_index: usize,
+    pub survivors: usize,
+    pub offspring: usize,
+
+    /// Number of members of each species; empty if speciation is disabled
+    #[wasm_bindgen(getter_with_clone, js_name = speciesSizes)]
+    pub species_sizes: Vec<u32>,
+}
+
+impl From<&ga::Statistics> for Statistics {
+    fn from(stats: &ga::Statistics) -> Self {
+        Self {
+            generation: stats.generation,
+            min_fitness: stats.min_fitness,
+            max_fitness: stats.max_fitness,
+            avg_fitness: stats.avg_fitness,
+            median_fitness: stats.median_fitness,
+            std_dev_fitness: stats.std_dev_fitness,
+            p10_fitness: stats.p10_fitness,
+            p90_fitness: stats.p90_fitness,
+            mean_distance: stats.mean_distance,
+            best_index: stats.best_index,
+            survivors: stats.survivors,
+            offspring: stats.offspring,
+            species_sizes: stats
+                .species_sizes
+                .iter()
+                .map(|&size| size as u32)
+                .collect(),
+        }
     }
 }
 
@@ -0,0 +1,68 @@
+use lib_simulation as sim;
+
+/// Number of floats describing a single animal:
+///
+/// | offset | value                                 |
+/// |--------|---------------------------------------|
+/// | 0      | x, in 0.0..=1.0                       |
+/// | 1      | y, in 0.0..=1.0                       |
+/// | 2      | rotation, in radians                  |
+/// | 3      | speed                                 |
+/// | 4      | satiation (foods eaten in generation) |
+/// | 5      | energy                                |
+/// | 6      | 1.0 if alive, 0.0 if starved          |
+pub const ANIMAL_STRIDE: usize = 7;
+
+/// Number of floats describing a single food: x and y, in 0.0..=1.0.
+pub const FOOD_STRIDE: usize = 2;
+
+/// Number of floats describing a single predator: x, y, rotation and the
+/// number of birds caught in generation.
+pub const PREDATOR_STRIDE: usize = 4;
+
+/// World packed into flat buffers, so that JS can read it through a
+/// `Float32Array` pointing straight into wasm memory, instead of receiving a
+/// fresh object per animal and food on each frame.
+#[derive(Debug, Default)]
+pub(crate) struct WorldView {
+    pub(crate) animals: Vec<f32>,
+    pub(crate) foods: Vec<f32>,
+    pub(crate) predators: Vec<f32>,
+}
+
+impl WorldView {
+    /// Overwrites buffers with the current state of `world`; buffers are
+    /// reused, so this allocates only when the world grows.
+    pub(crate) fn refresh(&mut self, world: &sim::World) {
+        self.animals.clear();
+
+        for animal in world.animals() {
+            self.animals.extend([
+                animal.position().x,
+                animal.position().y,
+                animal.rotation().angle(),
+                animal.speed(),
+                animal.satiation() as f32,
+                animal.energy(),
+                if animal.is_alive() { 1.0 } else { 0.0 },
+            ]);
+        }
+
+        self.foods.clear();
+
+        for food in world.foods() {
+            self.foods.extend([food.position().x, food.position().y]);
+        }
+
+        self.predators.clear();
+
+        for predator in world.predators() {
+            self.predators.extend([
+                predator.position().x,
+                predator.position().y,
+                predator.rotation().angle(),
+                predator.kills() as f32,
+            ]);
+        }
+    }
+}
@@ -0,0 +1,83 @@
+//! Checks that configs travel between JS and Rust without losing fields;
+//! doesn't need a browser, so it's run through `wasm-pack test --node`.
+
+#![cfg(target_arch = "wasm32")]
+
+use lib_simulation as sim;
+use serde_json::Value;
+use simulation_wasm::Simulation;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+/// Config whose every field differs from the default one, so that a field
+/// lost on the way would come back with its default value.
+fn config() -> sim::Config {
+    let Value::Object(mut fields) = serde_json::to_value(sim::Config::default()).unwrap() else {
+        unreachable!();
+    };
+
+    for (name, value) in &mut fields {
+        *value = match &*value {
+            Value::Bool(value) => Value::Bool(!value),
+            Value::Number(n) if n.is_u64() => match n.as_u64().unwrap() {
+                0 => 1.into(),
+                n => (2 * n).into(),
+            },
+            Value::Number(n) => match n.as_f64().unwrap() {
+                n if n == 0.0 => 0.5.into(),
+                n => (2.0 * n).into(),
+            },
+            Value::String(variant) => match variant.as_str() {
+                "satiation" => "survival".into(),
+                "toroidal" => "walled".into(),
+                _ => panic!("don't know how to change `{}`", name),
+            },
+            _ => panic!("don't know how to change `{}`", name),
+        };
+    }
+
+    serde_json::from_value(Value::Object(fields)).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn config_roundtrip() {
+    let config = config();
+
+    let sim = Simulation::from_seed(42, serde_wasm_bindgen::to_value(&config).unwrap()).unwrap();
+    let restored: sim::Config = serde_wasm_bindgen::from_value(sim.config().unwrap()).unwrap();
+
+    assert_eq!(restored, config);
+}
+
+#[wasm_bindgen_test]
+fn partial_config() {
+    let partial = js_sys::JSON::parse(r#"{ "world_animals": 5 }"#).unwrap();
+    let sim = Simulation::from_seed(42, partial).unwrap();
+    let config: sim::Config = serde_wasm_bindgen::from_value(sim.config().unwrap()).unwrap();
+
+    assert_eq!(
+        config,
+        sim::Config {
+            world_animals: 5,
+            ..Default::default()
+        }
+    );
+
+    let default = Simulation::from_seed(42, JsValue::UNDEFINED).unwrap();
+    let config: sim::Config = serde_wasm_bindgen::from_value(default.config().unwrap()).unwrap();
+
+    assert_eq!(config, sim::Config::default());
+}
+
+#[wasm_bindgen_test]
+fn invalid_config() {
+    for config in [
+        r#"{ "eye_cells": 0 }"#,
+        r#"{ "ga_mut_chance": 2 }"#,
+        r#"{ "world_animals": 0 }"#,
+    ] {
+        let config = js_sys::JSON::parse(config).unwrap();
+
+        assert!(Simulation::from_seed(42, config).is_err());
+    }
+}
@@ -0,0 +1,28 @@
+//! Runs under Node (`wasm-pack test --node -- --test fingerprint`), see
+//! `.github/workflows/ci.yml`.
+
+#![cfg(target_arch = "wasm32")]
+
+extern crate wasm_bindgen_test;
+use lib_simulation as sim;
+use wasm_bindgen_test::*;
+
+/// Mirrors `lib_simulation`'s `from_seed_fingerprint` test - both must
+/// produce the same hash for seeded runs to be reproducible across targets.
+#[wasm_bindgen_test]
+fn from_seed_fingerprint() {
+    let config = sim::Config {
+        sim_generation_length: 50,
+        ..Default::default()
+    };
+
+    let (mut sim, mut rng) = sim::Simulation::from_seed(config, 42);
+
+    sim.train(&mut rng);
+
+    for _ in 0..50 {
+        sim.step(&mut rng);
+    }
+
+    assert_eq!(sim.fingerprint(), 16446425090840397952);
+}
@@ -3,6 +3,7 @@
 #![cfg(target_arch = "wasm32")]
 
 extern crate wasm_bindgen_test;
+use wasm_bindgen::JsValue;
 use wasm_bindgen_test::*;
 
 wasm_bindgen_test_configure!(run_in_browser);
@@ -11,3 +12,61 @@ wasm_bindgen_test_configure!(run_in_browser);
 fn pass() {
     assert_eq!(1 + 1, 2);
 }
+
+#[wasm_bindgen_test]
+fn step_reports_finished_generations() {
+    let config =
+        js_sys::JSON::parse(r#"{ "sim_generation_length": 10, "world_animals": 5 }"#).unwrap();
+
+    let mut sim = simulation_wasm::Simulation::from_seed(42, config).unwrap();
+
+    for age in 1..=10 {
+        assert!(sim.step().is_none());
+        assert_eq!(sim.age(), age);
+    }
+
+    let stats = sim.step().unwrap();
+
+    assert_eq!(stats.generation, 0);
+    assert_eq!(stats.survivors + stats.offspring, 5);
+    assert_eq!(sim.generation(), 1);
+    assert_eq!(sim.age(), 0);
+
+    assert_eq!(sim.train().generation, 1);
+}
+
+#[wasm_bindgen_test]
+fn views_match_world() {
+    let mut sim = simulation_wasm::Simulation::from_seed(42, JsValue::UNDEFINED).unwrap();
+
+    sim.step();
+
+    let world = sim.world();
+    let animals = sim.animals_view().to_vec();
+    let foods = sim.foods_view().to_vec();
+
+    assert_eq!(
+        animals.len(),
+        world.animals.len() * simulation_wasm::ANIMAL_STRIDE
+    );
+    assert_eq!(
+        foods.len(),
+        world.foods.len() * simulation_wasm::FOOD_STRIDE
+    );
+
+    for (animal, packed) in world
+        .animals
+        .iter()
+        .zip(animals.chunks_exact(simulation_wasm::ANIMAL_STRIDE))
+    {
+        assert_eq!(&packed[..3], [animal.x, animal.y, animal.rotation]);
+    }
+
+    for (food, packed) in world
+        .foods
+        .iter()
+        .zip(foods.chunks_exact(simulation_wasm::FOOD_STRIDE))
+    {
+        assert_eq!(packed, [food.x, food.y]);
+    }
+}
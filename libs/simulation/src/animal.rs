@@ -1,6 +1,6 @@
 use crate::*;
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Animal {
     pub(crate) position: na::Point2<f32>,
     pub(crate) rotation: na::Rotation2<f32>,
@@ -8,10 +8,15 @@ pub struct Animal {
     pub(crate) eye: Eye,
     pub(crate) brain: Brain,
     pub(crate) satiation: usize,
+    pub(crate) energy: f32,
+    pub(crate) alive: bool,
+
+    /// Number of steps survived during the current generation
+    pub(crate) lifetime: usize,
 }
 
 impl Animal {
-    fn new(eye: Eye, brain: Brain, rng: &mut dyn RngCore) -> Self {
+    fn new(config: &Config, eye: Eye, brain: Brain, rng: &mut dyn RngCore) -> Self {
         Self {
             position: rng.random(),
             rotation: rng.random(),
@@ -19,26 +24,118 @@ impl Animal {
             eye,
             brain,
             satiation: 0,
+            energy: config.energy_initial,
+            alive: true,
+            lifetime: 0,
         }
     }
 
-    pub(crate) fn from_chromosome(chromosome: ga::Chromosome, rng: &mut dyn RngCore) -> Self {
-        let eye = Eye::default();
-        let brain = Brain::from_chromosome(chromosome, &eye);
+    pub(crate) fn from_chromosome(
+        config: &Config,
+        chromosome: ga::Chromosome,
+        rng: &mut dyn RngCore,
+    ) -> Self {
+        let (eye, brain) =
+            Self::decode(config, chromosome).expect("evolution preserves chromosome length");
+
+        Self::new(config, eye, brain, rng)
+    }
+
+    /// Returns length of chromosomes of animals described by `config`; see
+    /// [`Animal::as_chromosome()`].
+    pub(crate) fn chromosome_len(config: &Config) -> usize {
+        let eye_genes = if config.eye_evolve { Eye::GENES } else { 0 };
+
+        Brain::weight_count(&Eye::new(config)) + eye_genes
+    }
+
+    /// Splits chromosome into eye and brain; with [`Config::eye_evolve`],
+    /// eye's genes follow brain's weights.
+    pub(crate) fn decode(config: &Config, chromosome: ga::Chromosome) -> Result<(Eye, Brain)> {
+        Self::check_chromosome(config, &chromosome)?;
+
+        let mut genes: Vec<_> = chromosome.into_iter().collect();
+
+        let eye = if config.eye_evolve {
+            let eye_genes = genes.split_off(genes.len() - Eye::GENES);
+
+            Eye::from_genes(config, eye_genes.try_into().expect("length got checked"))
+        } else {
+            Eye::new(config)
+        };
+
+        let brain = Brain::from_chromosome(genes.into_iter().collect(), &eye);
+
+        Ok((eye, brain))
+    }
+
+    /// Checks that `chromosome` describes an animal of `config`.
+    pub(crate) fn check_chromosome(config: &Config, chromosome: &ga::Chromosome) -> Result<()> {
+        let expected = Self::chromosome_len(config);
 
-        Self::new(eye, brain, rng)
+        if chromosome.len() != expected {
+            return Err(Error::IncompatibleChromosome {
+                got: chromosome.len(),
+                expected,
+            });
+        }
+
+        Ok(())
     }
 
-    pub fn random(rng: &mut dyn RngCore) -> Self {
-        let eye = Eye::default();
+    pub(crate) fn from_genome(config: &Config, genome: Genome, rng: &mut dyn RngCore) -> Self {
+        Self::new(config, genome.eye, genome.brain, rng)
+    }
+
+    pub fn random(config: &Config, rng: &mut dyn RngCore) -> Self {
+        let eye = Eye::new(config);
 
         let brain = Brain::random(rng, &eye);
 
-        Self::new(eye, brain, rng)
+        Self::new(config, eye, brain, rng)
+    }
+
+    pub(crate) fn as_chromosome(&self, config: &Config) -> ga::Chromosome {
+        let weights = self.brain.as_chromosome().into_iter();
+
+        if config.eye_evolve {
+            weights.chain(self.eye.genes(config)).collect()
+        } else {
+            weights.collect()
+        }
+    }
+
+    pub(crate) fn fitness(&self, config: &Config) -> f32 {
+        match config.sim_fitness {
+            Fitness::Satiation => self.satiation as f32,
+
+            Fitness::Survival => {
+                self.lifetime as f32 + config.sim_fitness_energy_weight * self.energy
+            }
+        }
     }
 
-    pub(crate) fn as_chromosome(&self) -> ga::Chromosome {
-        self.brain.as_chromosome()
+    /// Eats a piece of food.
+    pub(crate) fn eat(&mut self, config: &Config) {
+        self.satiation += 1;
+        self.energy += config.energy_per_food;
+    }
+
+    /// Pays for the step that has just been made; dies when out of energy.
+    pub(crate) fn metabolize(&mut self, config: &Config) {
+        self.energy -= config.energy_cost_base + config.energy_cost_speed * self.speed;
+
+        if self.energy > 0.0 {
+            self.lifetime += 1;
+        } else {
+            self.die();
+        }
+    }
+
+    /// Starves or gets caught by a predator.
+    pub(crate) fn die(&mut self) {
+        self.energy = 0.0;
+        self.alive = false;
     }
 
     pub fn position(&self) -> na::Point2<f32> {
@@ -48,4 +145,33 @@ impl Animal {
     pub fn rotation(&self) -> na::Rotation2<f32> {
         self.rotation
     }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    pub fn eye(&self) -> &Eye {
+        &self.eye
+    }
+
+    /// Number of foods eaten during the current generation.
+    pub fn satiation(&self) -> usize {
+        self.satiation
+    }
+
+    pub fn energy(&self) -> f32 {
+        self.energy
+    }
+
+    /// Whether this animal hasn't starved or got caught yet; dead animals
+    /// stay in the world until the end of generation, but don't move or
+    /// eat.
+    pub fn is_alive(&self) -> bool {
+        self.alive
+    }
+
+    /// Number of steps survived during the current generation.
+    pub fn lifetime(&self) -> usize {
+        self.lifetime
+    }
 }
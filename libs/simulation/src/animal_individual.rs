@@ -6,26 +6,30 @@ pub struct AnimalIndividual {
 }
 
 impl AnimalIndividual {
-    pub fn from_animal(animal: &Animal) -> Self {
+    pub fn from_animal(animal: &Animal, config: &Config) -> Self {
         Self {
-            fitness: animal.satiation as f32,
-            chromosome: animal.as_chromosome(),
+            fitness: animal.fitness(config),
+            chromosome: animal.as_chromosome(config),
         }
     }
 
-    pub fn into_animal(self, rng: &mut dyn RngCore) -> Animal {
-        Animal::from_chromosome(self.chromosome, rng)
+    pub fn into_animal(self, config: &Config, rng: &mut dyn RngCore) -> Animal {
+        Animal::from_chromosome(config, self.chromosome, rng)
     }
-}
 
-impl ga::Individual for AnimalIndividual {
-    fn create(chromosome: ga::Chromosome) -> Self {
+    pub fn from_predator(predator: &Predator) -> Self {
         Self {
-            fitness: 0.0,
-            chromosome,
+            fitness: predator.kills as f32,
+            chromosome: predator.as_chromosome(),
         }
     }
 
+    pub fn into_predator(self, config: &Config, rng: &mut dyn RngCore) -> Predator {
+        Predator::from_chromosome(config, self.chromosome, rng)
+    }
+}
+
+impl ga::Candidate for AnimalIndividual {
     fn chromosome(&self) -> &ga::Chromosome {
         &self.chromosome
     }
@@ -34,3 +38,12 @@ impl ga::Individual for AnimalIndividual {
         self.fitness
     }
 }
+
+impl ga::Individual for AnimalIndividual {
+    fn create(chromosome: ga::Chromosome) -> Self {
+        Self {
+            fitness: 0.0,
+            chromosome,
+        }
+    }
+}
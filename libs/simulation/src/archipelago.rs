@@ -0,0 +1,401 @@
+use crate::*;
+
+/// Several simulations ("islands"), each with its own world and population,
+/// evolving side by side and exchanging their best animals every now and
+/// then (see [`ga::IslandModel`]).
+///
+/// Islands can be configured differently (e.g. use different mutation rates)
+/// to compare regional strategies, as long as their generations are equally
+/// long and their animals' chromosomes are equally long (i.e. eyes see the
+/// same things through the same number of cells), so that migrants fit.
+///
+/// With the `parallel` feature, islands are stepped and evolved on separate
+/// threads; since each of them has its own generator, results don't change.
+pub struct Archipelago {
+    islands: Vec<Island>,
+    migration: ga::Migration,
+}
+
+struct Island {
+    sim: Simulation,
+
+    /// Each island has a generator of its own, so that islands don't affect
+    /// each other's randomness
+    rng: ChaCha8Rng,
+}
+
+impl Archipelago {
+    /// Creates one island per config; fails if there are no configs or if
+    /// they describe islands that can't evolve together.
+    ///
+    /// Island `n` is driven by stream `n` of ChaCha8 seeded with `seed`, so
+    /// the first island behaves exactly like `Simulation::from_seed()` with
+    /// the same seed - up until the first migration.
+    pub fn from_seed(configs: Vec<Config>, migration: ga::Migration, seed: u64) -> Result<Self> {
+        Self::check(configs.iter())?;
+
+        let islands = configs
+            .into_iter()
+            .enumerate()
+            .map(|(idx, config)| {
+                let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+                rng.set_stream(idx as u64);
+
+                let sim = Simulation::random(config, &mut rng);
+
+                Island { sim, rng }
+            })
+            .collect();
+
+        Ok(Self { islands, migration })
+    }
+
+    /// Captures all the islands; see [`Snapshot`].
+    pub fn snapshots(&self) -> Vec<Snapshot> {
+        self.islands
+            .iter()
+            .map(|island| island.sim.snapshot(&island.rng))
+            .collect()
+    }
+
+    pub fn restore(snapshots: Vec<Snapshot>, migration: ga::Migration) -> Result<Self> {
+        Self::check(snapshots.iter().map(Snapshot::config))?;
+
+        let islands = snapshots
+            .into_iter()
+            .map(|snapshot| {
+                let (sim, rng) = Simulation::restore(snapshot)?;
+
+                Ok(Island { sim, rng })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self { islands, migration })
+    }
+
+    /// Checks that `configs` are valid and describe islands that can evolve
+    /// together, i.e. have equally long generations and animals able to
+    /// migrate between them.
+    fn check<'a>(configs: impl Iterator<Item = &'a Config>) -> Result<()> {
+        let mut configs = configs.enumerate();
+        let (_, first) = configs.next().ok_or(Error::NoIslands)?;
+
+        first.validate()?;
+
+        for (island, config) in configs {
+            config.validate()?;
+
+            if config.sim_generation_length != first.sim_generation_length {
+                return Err(Error::IncompatibleGenerations {
+                    island,
+                    got: config.sim_generation_length,
+                    expected: first.sim_generation_length,
+                });
+            }
+
+            let got = Animal::chromosome_len(config);
+            let expected = Animal::chromosome_len(first);
+
+            if got != expected {
+                return Err(Error::IncompatibleIslands {
+                    island,
+                    got,
+                    expected,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn islands(&self) -> impl ExactSizeIterator<Item = &Simulation> {
+        self.islands.iter().map(|island| &island.sim)
+    }
+
+    /// Steps all the islands; once generation of each of them is over,
+    /// evolves them, migrates animals between them and returns statistics of
+    /// each island.
+    ///
+    /// Islands whose generation ends early (because all of their animals
+    /// have died) wait for the others instead of being evolved prematurely,
+    /// so that each island's generation lasts as long as it would on its own.
+    pub fn step(&mut self) -> Option<Vec<ga::Statistics>> {
+        let advance = |island: &mut Island| {
+            island.sim.is_generation_over() || island.sim.advance(&mut island.rng)
+        };
+
+        #[cfg(feature = "parallel")]
+        let finished = self
+            .islands
+            .par_iter_mut()
+            .map(advance)
+            .reduce(|| true, |a, b| a & b);
+
+        #[cfg(not(feature = "parallel"))]
+        let finished = self
+            .islands
+            .iter_mut()
+            .map(advance)
+            .fold(true, |a, b| a & b);
+
+        if finished { Some(self.evolve()) } else { None }
+    }
+
+    pub fn train(&mut self) -> Vec<ga::Statistics> {
+        loop {
+            if let Some(summary) = self.step() {
+                return summary;
+            }
+        }
+    }
+
+    fn evolve(&mut self) -> Vec<ga::Statistics> {
+        let populations: Vec<_> = self
+            .islands
+            .iter()
+            .map(|island| island.sim.population())
+            .collect();
+
+        // Algorithms stay within their simulations (so that snapshots pick
+        // them up), the model just borrows them
+        let (gas, mut rngs): (Vec<_>, Vec<_>) = self
+            .islands
+            .iter_mut()
+            .map(|island| {
+                island.sim.age = 0;
+
+                (&mut island.sim.ga, &mut island.rng)
+            })
+            .unzip();
+
+        let (evolved_populations, stats) = ga::IslandModel::new(gas, self.migration.clone())
+            .evolve_with(&populations, |gas, populations| {
+                #[cfg(feature = "parallel")]
+                let islands = gas.par_iter_mut().zip(&mut rngs).zip(populations);
+
+                #[cfg(not(feature = "parallel"))]
+                let islands = gas.iter_mut().zip(&mut rngs).zip(populations);
+
+                islands
+                    .map(|((ga, rng), population)| ga.evolve(*rng, population))
+                    .collect()
+            });
+
+        for ((island, evolved_population), stats) in
+            self.islands.iter_mut().zip(evolved_populations).zip(&stats)
+        {
+            island
+                .sim
+                .repopulate(&mut island.rng, evolved_population, stats);
+        }
+
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Config {
+        Config {
+            sim_generation_length: 50,
+            ..Default::default()
+        }
+    }
+
+    fn archipelago(islands: usize) -> Archipelago {
+        Archipelago::from_seed(
+            vec![config(); islands],
+            ga::Migration::new(ga::Topology::Ring, 1, 1),
+            42,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn single_island_matches_simulation() {
+        let mut archipelago = archipelago(1);
+        let (mut sim, mut rng) = Simulation::from_seed(config(), 42);
+
+        for _ in 0..2 {
+            assert_eq!(archipelago.train(), vec![sim.train(&mut rng)]);
+        }
+
+        assert_eq!(archipelago.islands().next().unwrap().world(), sim.world());
+    }
+
+    #[test]
+    fn islands_differ() {
+        let archipelago = archipelago(2);
+        let mut islands = archipelago.islands();
+
+        assert_ne!(
+            islands.next().unwrap().world(),
+            islands.next().unwrap().world()
+        );
+    }
+
+    #[test]
+    fn migrates_best_animals() {
+        let mut archipelago = archipelago(3);
+        let stats = archipelago.train();
+
+        let islands: Vec<_> = archipelago.islands().collect();
+
+        for (idx, island) in islands.iter().enumerate() {
+            let immigrant = island.world().animals().last().unwrap();
+            let source = &stats[(idx + islands.len() - 1) % islands.len()];
+
+            assert_eq!(
+                immigrant.as_chromosome(island.config()),
+                source.best_chromosome
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_differently_shaped_islands() {
+        let configs = vec![
+            config(),
+            Config {
+                eye_cells: config().eye_cells + 1,
+                ..config()
+            },
+        ];
+
+        let result =
+            Archipelago::from_seed(configs, ga::Migration::new(ga::Topology::Ring, 1, 1), 42);
+
+        assert!(matches!(
+            result,
+            Err(Error::IncompatibleIslands { island: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_differently_long_generations() {
+        let configs = vec![
+            config(),
+            config(),
+            Config {
+                sim_generation_length: 60,
+                ..config()
+            },
+        ];
+
+        let result =
+            Archipelago::from_seed(configs, ga::Migration::new(ga::Topology::Ring, 1, 1), 42);
+
+        assert!(matches!(
+            result,
+            Err(Error::IncompatibleGenerations { island: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_no_islands() {
+        let migration = ga::Migration::new(ga::Topology::Ring, 1, 1);
+
+        assert!(matches!(
+            Archipelago::from_seed(Vec::new(), migration.clone(), 42),
+            Err(Error::NoIslands)
+        ));
+
+        assert!(matches!(
+            Archipelago::restore(Vec::new(), migration),
+            Err(Error::NoIslands)
+        ));
+    }
+
+    #[test]
+    fn restore_rejects_differently_shaped_islands() {
+        let snapshots = archipelago(1).snapshots();
+
+        let other = Archipelago::from_seed(
+            vec![Config {
+                eye_evolve: true,
+                ..config()
+            }],
+            ga::Migration::new(ga::Topology::Ring, 1, 1),
+            42,
+        )
+        .unwrap()
+        .snapshots();
+
+        let result = Archipelago::restore(
+            snapshots.into_iter().chain(other).collect(),
+            ga::Migration::new(ga::Topology::Ring, 1, 1),
+        );
+
+        assert!(matches!(
+            result,
+            Err(Error::IncompatibleIslands { island: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn starved_islands_wait_for_others() {
+        let starving = Config {
+            energy_initial: 1.0,
+            energy_cost_base: 1.0,
+            world_foods: 0,
+            ..config()
+        };
+
+        let mut archipelago = Archipelago::from_seed(
+            vec![config(), starving],
+            ga::Migration::new(ga::Topology::Ring, 1, 1),
+            42,
+        )
+        .unwrap();
+
+        let mut steps = 1;
+
+        while archipelago.step().is_none() {
+            steps += 1;
+
+            let starved = archipelago.islands().nth(1).unwrap();
+
+            assert!(starved.world().animals().iter().all(|a| !a.is_alive()));
+            assert_eq!(starved.age, 1);
+        }
+
+        // The first island lived through its whole generation, same as if it
+        // was on its own
+        let (mut sim, mut rng) = Simulation::from_seed(config(), 42);
+        let mut expected_steps = 1;
+
+        while sim.step(&mut rng).is_none() {
+            expected_steps += 1;
+        }
+
+        assert_eq!(steps, expected_steps);
+    }
+
+    #[test]
+    fn is_deterministic() {
+        let mut archipelago_a = archipelago(2);
+        let mut archipelago_b = archipelago(2);
+
+        for _ in 0..2 {
+            assert_eq!(archipelago_a.train(), archipelago_b.train());
+        }
+    }
+
+    #[test]
+    fn snapshots_resume_identically() {
+        let mut archipelago_a = archipelago(2);
+
+        archipelago_a.train();
+
+        let mut archipelago_b = Archipelago::restore(
+            archipelago_a.snapshots(),
+            ga::Migration::new(ga::Topology::Ring, 1, 1),
+        )
+        .unwrap();
+
+        assert_eq!(archipelago_a.train(), archipelago_b.train());
+    }
+}
@@ -0,0 +1,76 @@
+use crate::*;
+
+/// What happens at the edges of the world, i.e. of the unit square.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Boundary {
+    /// Opposite edges are glued together - animals leaving through one of
+    /// them re-enter through the other one, and can see (and eat) food
+    /// across them
+    #[default]
+    Toroidal,
+
+    /// Edges are walls that animals slide along
+    Walled,
+}
+
+impl Boundary {
+    /// Returns the shortest vector pointing from `from` to `to`; on a torus
+    /// that's the minimum image, which might cross an edge.
+    pub fn offset(self, from: na::Point2<f32>, to: na::Point2<f32>) -> na::Vector2<f32> {
+        let offset = to - from;
+
+        match self {
+            Boundary::Toroidal => offset.map(|d| d - d.round()),
+            Boundary::Walled => offset,
+        }
+    }
+
+    pub fn distance(self, a: na::Point2<f32>, b: na::Point2<f32>) -> f32 {
+        self.offset(a, b).norm()
+    }
+
+    /// Brings `position` back into the world after it has moved.
+    pub(crate) fn confine(self, position: &mut na::Point2<f32>) {
+        for coord in position.iter_mut() {
+            *coord = match self {
+                Boundary::Toroidal => na::wrap(*coord, 0.0, 1.0),
+                Boundary::Walled => coord.clamp(0.0, 1.0),
+            };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn toroidal_offset_crosses_edges() {
+        let offset =
+            Boundary::Toroidal.offset(na::Point2::new(0.9, 0.05), na::Point2::new(0.1, 0.5));
+
+        assert_relative_eq!(offset, na::Vector2::new(0.2, 0.45), epsilon = 1e-6);
+    }
+
+    #[test]
+    fn walled_offset_doesnt_cross_edges() {
+        let offset = Boundary::Walled.offset(na::Point2::new(0.9, 0.05), na::Point2::new(0.1, 0.5));
+
+        assert_relative_eq!(offset, na::Vector2::new(-0.8, 0.45), epsilon = 1e-6);
+    }
+
+    #[test]
+    fn confine() {
+        let mut position = na::Point2::new(1.25, -0.25);
+
+        Boundary::Toroidal.confine(&mut position);
+        assert_relative_eq!(position, na::Point2::new(0.25, 0.75));
+
+        let mut position = na::Point2::new(1.25, -0.25);
+
+        Boundary::Walled.confine(&mut position);
+        assert_eq!(position, na::Point2::new(1.0, 0.0));
+    }
+}
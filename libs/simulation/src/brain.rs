@@ -1,6 +1,6 @@
 use crate::*;
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Brain {
     pub(crate) nn: nn::Network,
 }
@@ -22,15 +22,66 @@ impl Brain {
         }
     }
 
+    /// Returns number of weights of brains fitting `eye`.
+    pub(crate) fn weight_count(eye: &Eye) -> usize {
+        nn::Network::weight_count(&Self::topology(eye))
+    }
+
+    /// Serializes brain's network; see `nn::Network::to_json()`.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(self.nn.to_json()?)
+    }
+
+    /// Deserializes brain for animals with eyes described by `config`,
+    /// rejecting networks of a different shape.
+    pub fn from_json(config: &Config, json: &str) -> Result<Self> {
+        let brain = Self {
+            nn: nn::Network::from_json(json)?,
+        };
+
+        brain.check(&Eye::new(config))?;
+
+        Ok(brain)
+    }
+
+    /// Checks that this brain's shape fits `eye`.
+    pub(crate) fn check(&self, eye: &Eye) -> Result<()> {
+        let topology = Self::topology(eye);
+
+        if self.nn.topology() != topology {
+            let neurons = |layers: &[nn::LayerTopology]| -> Vec<_> {
+                layers.iter().map(|layer| layer.neurons).collect()
+            };
+
+            return Err(Error::IncompatibleBrain {
+                got: neurons(&self.nn.topology()),
+                expected: neurons(&topology),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Packs given brains for `nn::NetworkBatch::propagate_into()`.
+    pub(crate) fn batch<'a>(brains: impl IntoIterator<Item = &'a Brain>) -> nn::NetworkBatch {
+        let networks: Vec<_> = brains.into_iter().map(|brain| &brain.nn).collect();
+
+        nn::NetworkBatch::new(&networks).expect("all brains share the same topology")
+    }
+
+    /// Output layer uses tanh, so that the brain can ask to turn in both
+    /// directions.
     fn topology(eye: &Eye) -> [nn::LayerTopology; 3] {
         [
+            nn::LayerTopology::new(eye.cells() * eye.channels().len()),
             nn::LayerTopology {
-                neurons: eye.cells(),
+                neurons: 2 * eye.cells(),
+                activation: nn::Activation::Relu,
             },
             nn::LayerTopology {
-                neurons: 2 * eye.cells(),
+                neurons: 2,
+                activation: nn::Activation::Tanh,
             },
-            nn::LayerTopology { neurons: 2 },
         ]
     }
 }
@@ -0,0 +1,301 @@
+use crate::*;
+use std::f32::consts::*;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Radius within which an animal eats a piece of food
+    pub food_size: f32,
+
+    pub eye_fov_range: f32,
+    pub eye_fov_angle: f32,
+    pub eye_cells: usize,
+
+    /// Whether birds see other birds, through a channel of its own
+    pub eye_see_animals: bool,
+
+    /// Whether birds and predators see walls of a walled world, through a
+    /// channel of its own
+    pub eye_see_walls: bool,
+
+    /// Whether birds' eye range and angle are evolved alongside their
+    /// brains, within the bounds below; `eye_fov_range` and `eye_fov_angle`
+    /// become just the starting point then.
+    ///
+    /// Number of cells stays fixed, since brain's shape depends on it.
+    pub eye_evolve: bool,
+    pub eye_fov_range_min: f32,
+    pub eye_fov_range_max: f32,
+    pub eye_fov_angle_min: f32,
+    pub eye_fov_angle_max: f32,
+
+    pub ga_mut_chance: f32,
+    pub ga_mut_coeff: f32,
+
+    /// Number of the best animals carried unchanged into the next
+    /// generation
+    pub ga_elitism: usize,
+
+    /// Animals whose genes differ by less than this on average belong to
+    /// the same species; 0.0 disables speciation
+    pub ga_species_threshold: f32,
+
+    /// Number of generations during which new species are protected...
+    pub ga_species_protection: usize,
+
+    /// ... by having their fitness multiplied by this
+    pub ga_species_boost: f32,
+
+    /// Energy each animal starts a generation with
+    pub energy_initial: f32,
+
+    /// Energy gained by eating a piece of food
+    pub energy_per_food: f32,
+
+    /// Energy spent on each step, regardless of movement...
+    pub energy_cost_base: f32,
+
+    /// ... plus energy spent per unit of distance flown, i.e. multiplied by
+    /// animal's speed; animals that run out of energy die
+    pub energy_cost_speed: f32,
+
+    /// Radius within which a predator catches a bird
+    pub predator_catch_size: f32,
+    pub predator_eye_fov_range: f32,
+    pub predator_eye_fov_angle: f32,
+    pub predator_eye_cells: usize,
+    pub predator_speed_min: f32,
+    pub predator_speed_max: f32,
+
+    pub sim_speed_min: f32,
+    pub sim_speed_max: f32,
+    pub sim_speed_accel: f32,
+    pub sim_rotation_accel: f32,
+
+    /// Number of steps after which the population is evolved (or earlier,
+    /// if all the animals die)
+    pub sim_generation_length: usize,
+
+    pub sim_fitness: Fitness,
+
+    /// With [`Fitness::Survival`], worth of each unit of energy left
+    /// relative to a single step survived
+    pub sim_fitness_energy_weight: f32,
+
+    pub world_animals: usize,
+    pub world_foods: usize,
+
+    /// Number of predators; 0 disables predators altogether, including
+    /// the channel through which birds see them
+    pub world_predators: usize,
+
+    pub world_boundary: Boundary,
+}
+
+/// How well an animal did during its generation.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Fitness {
+    /// Number of foods eaten
+    #[default]
+    Satiation,
+
+    /// Number of steps survived plus energy left at the end, weighted by
+    /// [`Config::sim_fitness_energy_weight`]
+    Survival,
+}
+
+impl Config {
+    /// Checks that all the values are within ranges the simulation can work
+    /// with, so that a config coming from the outside fails here instead of
+    /// panicking somewhere inside the simulation.
+    pub fn validate(&self) -> Result<()> {
+        Eye::check_params(self.eye_fov_range, self.eye_fov_angle, self.eye_cells)?;
+
+        Eye::check_params(
+            self.predator_eye_fov_range,
+            self.predator_eye_fov_angle,
+            self.predator_eye_cells,
+        )?;
+
+        if self.eye_evolve {
+            Eye::check_params(
+                self.eye_fov_range_min,
+                self.eye_fov_angle_min,
+                self.eye_cells,
+            )?;
+            Eye::check_params(
+                self.eye_fov_range_max,
+                self.eye_fov_angle_max,
+                self.eye_cells,
+            )?;
+
+            check(
+                "eye_fov_range_max",
+                self.eye_fov_range_max,
+                self.eye_fov_range_max >= self.eye_fov_range_min,
+                "at least eye_fov_range_min",
+            )?;
+
+            check(
+                "eye_fov_angle_max",
+                self.eye_fov_angle_max,
+                self.eye_fov_angle_max >= self.eye_fov_angle_min,
+                "at least eye_fov_angle_min",
+            )?;
+        }
+
+        check(
+            "ga_mut_chance",
+            self.ga_mut_chance,
+            (0.0..=1.0).contains(&self.ga_mut_chance),
+            "within 0..=1",
+        )?;
+
+        check_non_negative("ga_mut_coeff", self.ga_mut_coeff)?;
+        check_non_negative("ga_species_threshold", self.ga_species_threshold)?;
+
+        check(
+            "ga_species_boost",
+            self.ga_species_boost,
+            self.ga_species_boost.is_finite() && self.ga_species_boost >= 1.0,
+            "at least 1",
+        )?;
+
+        check_non_negative("food_size", self.food_size)?;
+        check_non_negative("predator_catch_size", self.predator_catch_size)?;
+        check_non_negative("sim_speed_accel", self.sim_speed_accel)?;
+        check_non_negative("sim_rotation_accel", self.sim_rotation_accel)?;
+
+        check_non_negative("sim_speed_min", self.sim_speed_min)?;
+
+        check(
+            "sim_speed_max",
+            self.sim_speed_max,
+            self.sim_speed_max.is_finite() && self.sim_speed_max >= self.sim_speed_min,
+            "at least sim_speed_min",
+        )?;
+
+        check_non_negative("predator_speed_min", self.predator_speed_min)?;
+
+        check(
+            "predator_speed_max",
+            self.predator_speed_max,
+            self.predator_speed_max.is_finite()
+                && self.predator_speed_max >= self.predator_speed_min,
+            "at least predator_speed_min",
+        )?;
+
+        // Roulette wheel selection can't work with negative fitness
+        check_non_negative("sim_fitness_energy_weight", self.sim_fitness_energy_weight)?;
+
+        check(
+            "world_animals",
+            self.world_animals as f32,
+            self.world_animals > 0,
+            "positive",
+        )?;
+
+        Ok(())
+    }
+}
+
+fn check(field: &'static str, value: f32, valid: bool, expected: &'static str) -> Result<()> {
+    if valid {
+        Ok(())
+    } else {
+        Err(Error::InvalidConfig {
+            field,
+            value,
+            expected,
+        })
+    }
+}
+
+fn check_non_negative(field: &'static str, value: f32) -> Result<()> {
+    check(
+        field,
+        value,
+        value.is_finite() && value >= 0.0,
+        "finite and non-negative",
+    )
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            food_size: 0.01,
+
+            eye_fov_range: 0.25,
+            eye_fov_angle: PI + FRAC_PI_4,
+            eye_cells: 9,
+            eye_see_animals: false,
+            eye_see_walls: false,
+            eye_evolve: false,
+            eye_fov_range_min: 0.05,
+            eye_fov_range_max: 0.5,
+            eye_fov_angle_min: FRAC_PI_4,
+            eye_fov_angle_max: 2.0 * PI,
+
+            ga_mut_chance: 0.01,
+            ga_mut_coeff: 0.3,
+            ga_elitism: 0,
+            ga_species_threshold: 0.0,
+            ga_species_protection: 3,
+            ga_species_boost: 1.5,
+
+            // Metabolism is disabled by default, so nobody ever starves
+            energy_initial: 1.0,
+            energy_per_food: 0.25,
+            energy_cost_base: 0.0,
+            energy_cost_speed: 0.0,
+
+            predator_catch_size: 0.02,
+            predator_eye_fov_range: 0.35,
+            predator_eye_fov_angle: FRAC_PI_2,
+            predator_eye_cells: 9,
+            predator_speed_min: 0.001,
+            predator_speed_max: 0.006,
+
+            sim_speed_min: 0.001,
+            sim_speed_max: 0.005,
+            sim_speed_accel: 0.2,
+            sim_rotation_accel: FRAC_PI_2,
+            sim_generation_length: 2500,
+            sim_fitness: Fitness::Satiation,
+            sim_fitness_energy_weight: 100.0,
+
+            world_animals: 40,
+            world_foods: 60,
+            world_predators: 0,
+            world_boundary: Boundary::Toroidal,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    #[test]
+    fn default_is_valid() {
+        Config::default().validate().unwrap();
+    }
+
+    #[test_case(Config { eye_cells: 0, ..Default::default() } ; "eye without cells")]
+    #[test_case(Config { predator_eye_fov_range: -1.0, ..Default::default() } ; "predator eye of negative range")]
+    #[test_case(Config { eye_evolve: true, eye_fov_range_min: 0.0, ..Default::default() } ; "evolved eye of zero range")]
+    #[test_case(Config { eye_evolve: true, eye_fov_angle_max: 0.1, ..Default::default() } ; "evolved eye of inverted angles")]
+    #[test_case(Config { ga_mut_chance: 2.0, ..Default::default() } ; "mutation chance above one")]
+    #[test_case(Config { ga_mut_chance: f32::NAN, ..Default::default() } ; "nan mutation chance")]
+    #[test_case(Config { ga_species_boost: 0.5, ..Default::default() } ; "species boost below one")]
+    #[test_case(Config { sim_speed_accel: -0.1, ..Default::default() } ; "negative acceleration")]
+    #[test_case(Config { sim_speed_min: 0.1, ..Default::default() } ; "inverted speeds")]
+    #[test_case(Config { predator_speed_max: f32::INFINITY, ..Default::default() } ; "infinite predator speed")]
+    #[test_case(Config { sim_fitness_energy_weight: -1.0, ..Default::default() } ; "negative energy weight")]
+    #[test_case(Config { world_animals: 0, ..Default::default() } ; "no animals")]
+    fn rejects(config: Config) {
+        assert!(config.validate().is_err());
+    }
+}
@@ -0,0 +1,78 @@
+use crate::*;
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("got snapshot version {got}, but version {expected} was expected")]
+    UnsupportedSnapshotVersion { got: u32, expected: u32 },
+
+    #[error("couldn't (de)serialize JSON snapshot: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("couldn't (de)serialize binary snapshot: {0}")]
+    Binary(#[from] postcard::Error),
+
+    #[error("couldn't (de)serialize brain: {0}")]
+    Network(#[from] nn::Error),
+
+    #[error("got brain with layers of {got:?} neurons, but {expected:?} were expected")]
+    IncompatibleBrain {
+        got: Vec<usize>,
+        expected: Vec<usize>,
+    },
+
+    #[error(
+        "got eye with range {fov_range}, angle {fov_angle} and {cells} cells, but all must be positive"
+    )]
+    InvalidEye {
+        fov_range: f32,
+        fov_angle: f32,
+        cells: usize,
+    },
+
+    #[error("got {field} = {value}, but it must be {expected}")]
+    InvalidConfig {
+        field: &'static str,
+        value: f32,
+        expected: &'static str,
+    },
+
+    #[error(
+        "got eye with {got_cells} cells seeing {got_channels:?}, but {expected_cells} cells seeing {expected_channels:?} were expected"
+    )]
+    IncompatibleEye {
+        got_cells: usize,
+        got_channels: Vec<Channel>,
+        expected_cells: usize,
+        expected_channels: Vec<Channel>,
+    },
+
+    #[error("got chromosome of {got} genes, but {expected} genes were expected")]
+    IncompatibleChromosome { got: usize, expected: usize },
+
+    #[error("got no islands")]
+    NoIslands,
+
+    #[error("island {island} has generations of {got} steps, but the first one has {expected}")]
+    IncompatibleGenerations {
+        island: usize,
+        got: usize,
+        expected: usize,
+    },
+
+    #[error("island {island} has chromosomes of {got} genes, but the first one has {expected}")]
+    IncompatibleIslands {
+        island: usize,
+        got: usize,
+        expected: usize,
+    },
+
+    #[error("got {got} {what}, but config asks for {expected}")]
+    IncompatiblePopulation {
+        what: &'static str,
+        got: usize,
+        expected: usize,
+    },
+}
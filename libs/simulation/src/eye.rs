@@ -1,19 +1,80 @@
 use crate::*;
 use std::f32::consts::*;
 
-const FOV_RANGE: f32 = 0.25;
-const FOV_ANGLE: f32 = PI + FRAC_PI_4;
-const CELLS: usize = 9;
-
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Eye {
     fov_range: f32,
     fov_angle: f32,
     cells: usize,
+
+    /// Kinds of things this eye tells apart; each cell outputs one value
+    /// per channel
+    channels: Vec<Channel>,
 }
 
 impl Eye {
-    fn new(fov_range: f32, fov_angle: f32, cells: usize) -> Self {
+    /// Number of genes describing bird's eye when [`Config::eye_evolve`] is
+    /// enabled: its range and angle.
+    pub(crate) const GENES: usize = 2;
+
+    /// Creates bird's eye, which sees foods and - depending on the config -
+    /// other birds, predators and walls.
+    pub(crate) fn new(config: &Config) -> Self {
+        Self::bird(config, config.eye_fov_range, config.eye_fov_angle)
+    }
+
+    /// Creates bird's eye out of its genes; see [`Eye::genes()`].
+    pub(crate) fn from_genes(config: &Config, [range, angle]: [f32; Self::GENES]) -> Self {
+        Self::bird(
+            config,
+            decode(range, config.eye_fov_range_min, config.eye_fov_range_max),
+            decode(angle, config.eye_fov_angle_min, config.eye_fov_angle_max),
+        )
+    }
+
+    /// Encodes range and angle as genes within `-1.0..=1.0`, relative to the
+    /// bounds given by config; genes mutated past these get clamped.
+    pub(crate) fn genes(&self, config: &Config) -> [f32; Self::GENES] {
+        [
+            encode(
+                self.fov_range,
+                config.eye_fov_range_min,
+                config.eye_fov_range_max,
+            ),
+            encode(
+                self.fov_angle,
+                config.eye_fov_angle_min,
+                config.eye_fov_angle_max,
+            ),
+        ]
+    }
+
+    fn bird(config: &Config, fov_range: f32, fov_angle: f32) -> Self {
+        let mut channels = vec![Channel::Food];
+
+        if config.eye_see_animals {
+            channels.push(Channel::Animal);
+        }
+
+        if config.world_predators > 0 {
+            channels.push(Channel::Predator);
+        }
+
+        Self::with_params(fov_range, fov_angle, config.eye_cells).with_channels(channels, config)
+    }
+
+    /// Creates predator's eye, which sees birds and - depending on the
+    /// config - walls.
+    pub(crate) fn predator(config: &Config) -> Self {
+        Self::with_params(
+            config.predator_eye_fov_range,
+            config.predator_eye_fov_angle,
+            config.predator_eye_cells,
+        )
+        .with_channels(vec![Channel::Animal], config)
+    }
+
+    fn with_params(fov_range: f32, fov_angle: f32, cells: usize) -> Self {
         assert!(fov_range > 0.0);
         assert!(fov_angle > 0.0);
         assert!(cells > 0);
@@ -21,23 +82,127 @@ impl Eye {
             fov_range,
             fov_angle,
             cells,
+            channels: vec![Channel::Food],
+        }
+    }
+
+    /// Checks invariants asserted by `with_params()`, for eyes (and configs)
+    /// that come from the outside, e.g. from a snapshot.
+    pub(crate) fn check_params(fov_range: f32, fov_angle: f32, cells: usize) -> Result<()> {
+        let positive = |value: f32| value.is_finite() && value > 0.0;
+
+        if positive(fov_range) && positive(fov_angle) && cells > 0 {
+            Ok(())
+        } else {
+            Err(Error::InvalidEye {
+                fov_range,
+                fov_angle,
+                cells,
+            })
         }
     }
 
+    /// Checks that this eye is valid and produces the same values as
+    /// `expected`, so that brains made for either fit both.
+    pub(crate) fn check(&self, expected: &Eye) -> Result<()> {
+        Self::check_params(self.fov_range, self.fov_angle, self.cells)?;
+
+        if self.cells != expected.cells || self.channels != expected.channels {
+            return Err(Error::IncompatibleEye {
+                got_cells: self.cells,
+                got_channels: self.channels.clone(),
+                expected_cells: expected.cells,
+                expected_channels: expected.channels.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Sets channels, adding walls if they are both enabled and present.
+    fn with_channels(mut self, mut channels: Vec<Channel>, config: &Config) -> Self {
+        if config.eye_see_walls && config.world_boundary == Boundary::Walled {
+            channels.push(Channel::Wall);
+        }
+
+        self.channels = channels;
+        self
+    }
+
+    pub fn fov_range(&self) -> f32 {
+        self.fov_range
+    }
+
+    pub fn fov_angle(&self) -> f32 {
+        self.fov_angle
+    }
+
     pub fn cells(&self) -> usize {
         self.cells
     }
 
-    pub fn process_vision(
+    pub fn channels(&self) -> &[Channel] {
+        &self.channels
+    }
+
+    pub fn sees(&self, channel: Channel) -> bool {
+        self.channels.contains(&channel)
+    }
+
+    /// Number of values produced by this eye, i.e. size of brain's input.
+    pub fn inputs(&self) -> usize {
+        self.cells * self.channels.len()
+    }
+
+    /// Returns what's visible from given position, laid out cell by cell:
+    /// `vision[cell * channels + channel]`.
+    ///
+    /// Objects farther than `fov_range` are ignored, so it's enough to pass
+    /// the ones nearby - in the same order as they appear in the world,
+    /// though, to get bit-identical results.
+    pub fn process_vision<'a, V>(
         &self,
         position: na::Point2<f32>,
         rotation: na::Rotation2<f32>,
-        foods: &[Food],
-    ) -> Vec<f32> {
-        let mut cells = vec![0.0; self.cells];
+        objects: impl IntoIterator<Item = &'a V>,
+        boundary: Boundary,
+    ) -> Vec<f32>
+    where
+        V: Visible + 'a,
+    {
+        let mut vision = vec![0.0; self.inputs()];
 
-        for food in foods {
-            let vec = food.position - position;
+        self.see(position, rotation, objects, boundary, &mut vision);
+        self.see_walls(position, rotation, boundary, &mut vision);
+
+        vision
+    }
+
+    /// Adds `objects` into `vision`, which is laid out as in
+    /// `process_vision()`; objects of channels this eye doesn't have are
+    /// ignored.
+    pub(crate) fn see<'a, V>(
+        &self,
+        position: na::Point2<f32>,
+        rotation: na::Rotation2<f32>,
+        objects: impl IntoIterator<Item = &'a V>,
+        boundary: Boundary,
+        vision: &mut [f32],
+    ) where
+        V: Visible + 'a,
+    {
+        assert_eq!(vision.len(), self.inputs());
+
+        for object in objects {
+            if !object.is_visible() {
+                continue;
+            }
+
+            let Some(channel) = self.channel_idx(object.channel()) else {
+                continue;
+            };
+
+            let vec = boundary.offset(position, object.position());
 
             let dist = vec.norm();
 
@@ -61,30 +226,78 @@ impl Eye {
 
             let cell = cell * (self.cells as f32);
 
-            let cell = (cell as usize).min(cells.len() - 1);
+            let cell = (cell as usize).min(self.cells - 1);
 
             let energy = (self.fov_range - dist) / self.fov_range;
 
-            cells[cell] += energy;
+            vision[cell * self.channels.len() + channel] += energy;
         }
+    }
 
-        cells
+    /// Adds walls into `vision` (if this eye sees them and there are any);
+    /// each cell looks along its middle and sees the nearest wall there.
+    pub(crate) fn see_walls(
+        &self,
+        position: na::Point2<f32>,
+        rotation: na::Rotation2<f32>,
+        boundary: Boundary,
+        vision: &mut [f32],
+    ) {
+        if boundary != Boundary::Walled {
+            return;
+        }
+
+        let Some(channel) = self.channel_idx(Channel::Wall) else {
+            return;
+        };
+
+        for cell in 0..self.cells {
+            let angle =
+                -self.fov_angle / 2.0 + (cell as f32 + 0.5) * self.fov_angle / self.cells as f32;
+            let dir = na::Rotation2::new(rotation.angle() + angle) * na::Vector2::y();
+
+            let dist_along = |pos: f32, dir: f32| {
+                if dir > 0.0 {
+                    (1.0 - pos) / dir
+                } else if dir < 0.0 {
+                    -pos / dir
+                } else {
+                    f32::INFINITY
+                }
+            };
+
+            let dist = dist_along(position.x, dir.x).min(dist_along(position.y, dir.y));
+
+            if dist < self.fov_range {
+                vision[cell * self.channels.len() + channel] +=
+                    (self.fov_range - dist) / self.fov_range;
+            }
+        }
+    }
+
+    fn channel_idx(&self, channel: Channel) -> Option<usize> {
+        self.channels.iter().position(|&other| other == channel)
     }
 }
 
-impl Default for Eye {
-    fn default() -> Self {
-        Self {
-            fov_range: FOV_RANGE,
-            fov_angle: FOV_ANGLE,
-            cells: CELLS,
-        }
+/// Maps gene from `-1.0..=1.0` into `min..=max`.
+fn decode(gene: f32, min: f32, max: f32) -> f32 {
+    min + (max - min) * (gene.clamp(-1.0, 1.0) + 1.0) / 2.0
+}
+
+/// Maps value from `min..=max` into `-1.0..=1.0`.
+fn encode(value: f32, min: f32, max: f32) -> f32 {
+    if max > min {
+        2.0 * (value - min) / (max - min) - 1.0
+    } else {
+        0.0
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use approx::assert_relative_eq;
     use test_case::test_case;
 
     struct TestCase {
@@ -107,12 +320,15 @@ mod tests {
 
     impl TestCase {
         fn run(self) {
-            let eye = Eye::new(self.fov_range, self.fov_angle, TEST_EYE_CELLS);
+            let eye = Eye::with_params(self.fov_range, self.fov_angle, TEST_EYE_CELLS);
 
             let actual_vision = eye.process_vision(
                 na::Point2::new(self.x, self.y),
                 na::Rotation2::new(self.rot),
                 &self.foods,
+                // Cases below are laid out with plain, non-wrapping
+                // distances in mind
+                Boundary::Walled,
             );
 
             let actual_vision: Vec<_> = actual_vision
@@ -250,4 +466,132 @@ mod tests {
         }
         .run()
     }
+
+    #[test]
+    fn sees_across_edges() {
+        let eye = Eye::with_params(0.25, FRAC_PI_2, TEST_EYE_CELLS);
+        let foods = [food(0.5, 0.05)];
+
+        // Flying "up", right below the top edge - food is just across it
+        let vision = |boundary| {
+            eye.process_vision(
+                na::Point2::new(0.5, 0.95),
+                na::Rotation2::new(0.0),
+                &foods,
+                boundary,
+            )
+        };
+
+        let toroidal = vision(Boundary::Toroidal);
+
+        assert!(toroidal[TEST_EYE_CELLS / 2] > 0.5);
+        assert_eq!(toroidal.iter().filter(|&&cell| cell > 0.0).count(), 1);
+
+        assert!(vision(Boundary::Walled).iter().all(|&cell| cell == 0.0));
+    }
+
+    struct Thing {
+        position: na::Point2<f32>,
+        channel: Channel,
+    }
+
+    impl Visible for Thing {
+        fn position(&self) -> na::Point2<f32> {
+            self.position
+        }
+
+        fn channel(&self) -> Channel {
+            self.channel
+        }
+    }
+
+    fn thing(x: f32, y: f32, channel: Channel) -> Thing {
+        Thing {
+            position: na::Point2::new(x, y),
+            channel,
+        }
+    }
+
+    #[test]
+    fn separates_channels() {
+        let eye = Eye::with_params(1.0, FRAC_PI_2, 3)
+            .with_channels(vec![Channel::Food, Channel::Predator], &Config::default());
+
+        let vision = eye.process_vision(
+            na::Point2::new(0.5, 0.5),
+            na::Rotation2::new(0.0),
+            &[
+                thing(0.5, 0.75, Channel::Predator),
+                thing(0.5, 0.9, Channel::Food),
+                // Not seen by this eye
+                thing(0.5, 0.6, Channel::Animal),
+            ],
+            Boundary::Walled,
+        );
+
+        assert_eq!(eye.inputs(), 6);
+        assert_relative_eq!(
+            vision.as_slice(),
+            [0.0, 0.0, 0.6, 0.75, 0.0, 0.0].as_slice()
+        );
+    }
+
+    #[test]
+    fn sees_walls() {
+        let config = Config {
+            eye_see_walls: true,
+            world_boundary: Boundary::Walled,
+            ..Default::default()
+        };
+
+        let eye = Eye::with_params(0.25, 0.1, 1).with_channels(vec![Channel::Food], &config);
+
+        let vision = |position, boundary| {
+            eye.process_vision::<Food>(position, na::Rotation2::new(0.0), &[], boundary)
+        };
+
+        assert_eq!(eye.channels(), [Channel::Food, Channel::Wall]);
+
+        // Looking "up", at the top wall
+        assert_relative_eq!(
+            vision(na::Point2::new(0.5, 0.9), Boundary::Walled).as_slice(),
+            [0.0, 0.6].as_slice(),
+            epsilon = 1e-5
+        );
+
+        assert_eq!(
+            vision(na::Point2::new(0.5, 0.5), Boundary::Walled),
+            [0.0, 0.0]
+        );
+        assert_eq!(
+            vision(na::Point2::new(0.5, 0.9), Boundary::Toroidal),
+            [0.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn genes_roundtrip() {
+        let config = Config {
+            eye_evolve: true,
+            ..Default::default()
+        };
+
+        let eye = Eye::new(&config);
+        let genes = eye.genes(&config);
+
+        assert!(genes.iter().all(|gene| (-1.0..=1.0).contains(gene)));
+
+        let decoded = Eye::from_genes(&config, genes);
+
+        assert_relative_eq!(decoded.fov_range(), eye.fov_range(), epsilon = 1e-6);
+        assert_relative_eq!(decoded.fov_angle(), eye.fov_angle(), epsilon = 1e-6);
+        assert_eq!(decoded.cells(), eye.cells());
+        assert_eq!(decoded.channels(), eye.channels());
+
+        // Genes mutated past the bounds get clamped
+        let eye = Eye::from_genes(&config, [5.0, -5.0]);
+
+        assert_eq!(eye.fov_range(), config.eye_fov_range_max);
+        assert_eq!(eye.fov_angle(), config.eye_fov_angle_min);
+    }
 }
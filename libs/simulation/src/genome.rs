@@ -0,0 +1,50 @@
+use crate::*;
+
+/// Everything an animal inherits - its eye and brain - e.g. to export the
+/// fittest one and evaluate it later; see [`Simulation::best_genome()`].
+///
+/// Eye differs from the one described by config only with
+/// [`Config::eye_evolve`], but it's kept either way, so that evolved range
+/// and angle don't get lost.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Genome {
+    pub(crate) eye: Eye,
+    pub(crate) brain: Brain,
+}
+
+impl Genome {
+    pub(crate) fn decode(config: &Config, chromosome: ga::Chromosome) -> Result<Self> {
+        let (eye, brain) = Animal::decode(config, chromosome)?;
+
+        Ok(Self { eye, brain })
+    }
+
+    pub fn eye(&self) -> &Eye {
+        &self.eye
+    }
+
+    pub fn brain(&self) -> &Brain {
+        &self.brain
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    /// Deserializes genome of animals described by `config`, rejecting eyes
+    /// that see different things and brains of a different shape.
+    pub fn from_json(config: &Config, json: &str) -> Result<Self> {
+        let genome: Self = serde_json::from_str(json)?;
+
+        Eye::check_params(
+            genome.eye.fov_range(),
+            genome.eye.fov_angle(),
+            genome.eye.cells(),
+        )?;
+
+        genome.eye.check(&Eye::new(config))?;
+        genome.brain.check(&genome.eye)?;
+
+        Ok(genome)
+    }
+}
@@ -0,0 +1,200 @@
+use crate::*;
+
+/// Upper bound on the number of cells along each axis, so that sparse
+/// queries don't end up visiting mostly empty cells.
+const MAX_SIDE: usize = 128;
+
+/// Uniform grid over the world, bucketing points by the cell they fall
+/// into, so that radius queries only have to look at nearby cells instead
+/// of at every point.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Grid {
+    /// Number of cells along each axis
+    side: usize,
+
+    /// Indices of points within each cell, row by row
+    cells: Vec<Vec<usize>>,
+}
+
+impl Grid {
+    /// Re-buckets all the `points`, picking resolution so that there's
+    /// around one point per cell.
+    pub(crate) fn rebuild(&mut self, points: impl ExactSizeIterator<Item = na::Point2<f32>>) {
+        self.side = ((points.len() as f32).sqrt() as usize).clamp(1, MAX_SIDE);
+
+        self.cells.resize_with(self.side * self.side, Vec::new);
+        self.cells.truncate(self.side * self.side);
+
+        for cell in &mut self.cells {
+            cell.clear();
+        }
+
+        for (idx, point) in points.enumerate() {
+            let cell = self.cell_of(point);
+
+            self.cells[cell].push(idx);
+        }
+    }
+
+    /// Updates point `idx` after it moved from `from` to `to`.
+    pub(crate) fn relocate(&mut self, idx: usize, from: na::Point2<f32>, to: na::Point2<f32>) {
+        let (from, to) = (self.cell_of(from), self.cell_of(to));
+
+        if from == to {
+            return;
+        }
+
+        let cell = &mut self.cells[from];
+
+        let pos = cell
+            .iter()
+            .position(|&other| other == idx)
+            .expect("point is stored in the cell of its old position");
+
+        cell.swap_remove(pos);
+        self.cells[to].push(idx);
+    }
+
+    /// Collects indices of all the points that might lie within `radius`
+    /// from `center` into `out` - callers still have to check the actual
+    /// distance.
+    ///
+    /// Indices are sorted, so that callers process points in the same order
+    /// as they would when iterating over all of them; this keeps
+    /// floating-point sums bit-identical.
+    pub(crate) fn query(
+        &self,
+        center: na::Point2<f32>,
+        radius: f32,
+        boundary: Boundary,
+        out: &mut Vec<usize>,
+    ) {
+        out.clear();
+
+        if self.cells.is_empty() {
+            return;
+        }
+
+        let xs = self.span(center.x, radius, boundary);
+        let ys = self.span(center.y, radius, boundary);
+
+        for y in ys.clone() {
+            for x in xs.clone() {
+                out.extend(&self.cells[y * self.side + x]);
+            }
+        }
+
+        out.sort_unstable();
+    }
+
+    /// Returns cells along a single axis that overlap with `coord ± radius`.
+    fn span(
+        &self,
+        coord: f32,
+        radius: f32,
+        boundary: Boundary,
+    ) -> impl Iterator<Item = usize> + Clone {
+        let side = self.side as isize;
+        let lo = ((coord - radius) * self.side as f32).floor() as isize;
+        let hi = ((coord + radius) * self.side as f32).floor() as isize;
+
+        let (start, count) = match boundary {
+            Boundary::Toroidal if hi - lo + 1 >= side => (0, side),
+            Boundary::Toroidal => (lo.rem_euclid(side), hi - lo + 1),
+
+            Boundary::Walled => {
+                let (lo, hi) = (lo.max(0), hi.min(side - 1));
+
+                (lo, (hi - lo + 1).max(0))
+            }
+        };
+
+        (start..start + count).map(move |cell| (cell % side) as usize)
+    }
+
+    fn cell_of(&self, point: na::Point2<f32>) -> usize {
+        let coord = |coord: f32| ((coord * self.side as f32) as usize).min(self.side - 1);
+
+        coord(point.y) * self.side + coord(point.x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test_case::test_case;
+
+    fn points(rng: &mut ChaCha8Rng, len: usize) -> Vec<na::Point2<f32>> {
+        (0..len).map(|_| rng.random()).collect()
+    }
+
+    fn assert_finds_all(grid: &Grid, points: &[na::Point2<f32>], radius: f32, boundary: Boundary) {
+        let mut found = Vec::new();
+
+        for center in points {
+            grid.query(*center, radius, boundary, &mut found);
+
+            assert!(found.is_sorted());
+
+            for (idx, point) in points.iter().enumerate() {
+                if boundary.distance(*center, *point) <= radius {
+                    assert!(found.contains(&idx), "missing {idx} near {center}");
+                }
+            }
+        }
+    }
+
+    #[test_case(Boundary::Toroidal, 0.02)]
+    #[test_case(Boundary::Toroidal, 0.25)]
+    #[test_case(Boundary::Toroidal, 0.75)]
+    #[test_case(Boundary::Walled, 0.02)]
+    #[test_case(Boundary::Walled, 0.25)]
+    #[test_case(Boundary::Walled, 0.75)]
+    fn finds_all_points_within_radius(boundary: Boundary, radius: f32) {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let points = points(&mut rng, 200);
+
+        let mut grid = Grid::default();
+
+        grid.rebuild(points.iter().copied());
+        assert_finds_all(&grid, &points, radius, boundary);
+    }
+
+    #[test]
+    fn skips_distant_cells() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let points = points(&mut rng, 400);
+
+        let mut grid = Grid::default();
+        let mut found = Vec::new();
+
+        grid.rebuild(points.iter().copied());
+        grid.query(
+            na::Point2::new(0.5, 0.5),
+            0.05,
+            Boundary::Toroidal,
+            &mut found,
+        );
+
+        assert!(found.len() < points.len() / 10);
+    }
+
+    #[test]
+    fn relocates_points() {
+        let mut rng = ChaCha8Rng::from_seed(Default::default());
+        let mut points = points(&mut rng, 100);
+
+        let mut grid = Grid::default();
+
+        grid.rebuild(points.iter().copied());
+
+        for (idx, point) in points.iter_mut().enumerate() {
+            let to = rng.random();
+
+            grid.relocate(idx, *point, to);
+            *point = to;
+        }
+
+        assert_finds_all(&grid, &points, 0.1, Boundary::Toroidal);
+    }
+}
@@ -1,59 +1,226 @@
 mod animal;
 mod animal_individual;
+mod archipelago;
+mod boundary;
 mod brain;
+mod config;
+mod error;
 mod eye;
 mod food;
+mod genome;
+mod grid;
+mod predator;
+mod snapshot;
+mod visible;
 mod world;
 
-pub use self::{animal::*, animal_individual::*, brain::*, eye::*, food::*, world::*};
+use self::grid::Grid;
+pub use self::{
+    animal::*, animal_individual::*, archipelago::*, boundary::*, brain::*, config::*, error::*,
+    eye::*, food::*, genome::*, predator::*, snapshot::*, visible::*, world::*,
+};
 use lib_genetic_algorithm as ga;
 use lib_neural_network as nn;
 use nalgebra as na;
-use rand::{Rng, RngCore};
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use std::{convert, f32::consts::FRAC_PI_2};
-
-const SPEED_MIN: f32 = 0.001;
-
-const SPEED_MAX: f32 = 0.005;
-
-const SPEED_ACCEL: f32 = 0.2;
-
-const ROTATION_ACCEL: f32 = FRAC_PI_2;
-
-const GENERATION_LENGTH: usize = 2500;
+// Rayon's thread pool doesn't work in the browser, where it'd fail only at
+// runtime
+#[cfg(all(feature = "parallel", target_arch = "wasm32"))]
+compile_error!("the `parallel` feature isn't supported on wasm32");
 
 pub struct Simulation {
+    config: Config,
     world: World,
     ga: ga::GeneticAlgorithm<ga::RouletteWheelSelection>,
     age: usize,
+    history: ga::History,
+
+    /// Brains of all the animals, rebuilt each time the population changes
+    brains: nn::NetworkBatch,
+    brain_inputs: Vec<f32>,
+    brain_buffers: nn::Buffers,
+
+    /// Predators evolve separately from animals, with their own algorithm
+    /// and history
+    predator_ga: ga::GeneticAlgorithm<ga::RouletteWheelSelection>,
+    predator_history: ga::History,
+    predator_brains: nn::NetworkBatch,
+    predator_brain_inputs: Vec<f32>,
+    predator_brain_buffers: nn::Buffers,
 }
 
 impl Simulation {
-    pub fn random(rng: &mut dyn RngCore) -> Self {
-        let world = World::random(rng);
+    pub fn random(config: Config, rng: &mut dyn RngCore) -> Self {
+        let world = World::random(&config, rng);
 
-        let ga = ga::GeneticAlgorithm::new(
-            ga::RouletteWheelSelection,
-            ga::UniformCrossover,
-            ga::GaussianMutation::new(0.01, 0.3),
-        );
+        Self::from_world(config, world)
+    }
+
+    /// Creates a simulation where all the animals share the same `genome`;
+    /// see [`World::from_genome()`].
+    pub fn from_genome(config: Config, genome: &Genome, rng: &mut dyn RngCore) -> Self {
+        let world = World::from_genome(&config, genome, rng);
 
-        Self { world, ga, age: 0 }
+        Self::from_world(config, world)
+    }
+
+    fn from_world(config: Config, mut world: World) -> Self {
+        world.index_foods();
+
+        let new_ga = || {
+            ga::GeneticAlgorithm::new(
+                ga::RouletteWheelSelection,
+                ga::UniformCrossover,
+                ga::GaussianMutation::new(config.ga_mut_chance, config.ga_mut_coeff),
+            )
+            .with_elitism(config.ga_elitism)
+        };
+
+        let mut ga = new_ga();
+        let predator_ga = new_ga();
+
+        if config.ga_species_threshold > 0.0 {
+            ga = ga.with_speciation(
+                ga::Speciation::new(ga::MeanAbsoluteDistance, config.ga_species_threshold)
+                    .with_protection(config.ga_species_protection, config.ga_species_boost),
+            );
+        }
+
+        let brains = Brain::batch(world.animals.iter().map(|animal| &animal.brain));
+
+        let predator_brains = Brain::batch(world.predators.iter().map(|predator| &predator.brain));
+
+        Self {
+            config,
+            world,
+            ga,
+            age: 0,
+            history: ga::History::default(),
+            brains,
+            brain_inputs: Vec::new(),
+            brain_buffers: nn::Buffers::default(),
+            predator_ga,
+            predator_history: ga::History::default(),
+            predator_brains,
+            predator_brain_inputs: Vec::new(),
+            predator_brain_buffers: nn::Buffers::default(),
+        }
+    }
+
+    /// Creates a simulation from `seed`, returning it together with the
+    /// generator that should drive its subsequent `step()`s / `train()`s.
+    ///
+    /// ChaCha8 is portable and all the floating-point math goes through
+    /// `libm`, so identical seeds and configs produce bit-identical worlds
+    /// and statistics on every target, including wasm.
+    pub fn from_seed(config: Config, seed: u64) -> (Self, ChaCha8Rng) {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let sim = Self::random(config, &mut rng);
+
+        (sim, rng)
+    }
+
+    /// Captures the entire state of this simulation; see [`Snapshot`].
+    ///
+    /// Out of statistics, only the most recent generation's are kept, so
+    /// that snapshots don't grow over long runs; see
+    /// [`Simulation::snapshot_with_history()`].
+    pub fn snapshot(&self, rng: &ChaCha8Rng) -> Snapshot {
+        Snapshot::new(self, rng, false)
+    }
+
+    /// Captures the entire state of this simulation together with
+    /// statistics of all the generations evolved so far.
+    pub fn snapshot_with_history(&self, rng: &ChaCha8Rng) -> Snapshot {
+        Snapshot::new(self, rng, true)
+    }
+
+    /// Resumes simulation from a snapshot, failing if it's inconsistent
+    /// (e.g. its brains don't fit its config).
+    pub fn restore(snapshot: Snapshot) -> Result<(Self, ChaCha8Rng)> {
+        snapshot.restore()
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
     }
 
     pub fn world(&self) -> &World {
         &self.world
     }
 
-    pub fn step(&mut self, rng: &mut dyn RngCore) -> Option<ga::Statistics> {
-        self.process_collisions(rng);
-        self.process_brains();
-        self.process_movements();
+    /// Number of generations evolved so far.
+    pub fn generation(&self) -> usize {
+        self.ga.generation()
+    }
 
-        self.age += 1;
+    /// Number of steps performed within the current generation.
+    pub fn age(&self) -> usize {
+        self.age
+    }
 
-        if self.age > GENERATION_LENGTH {
+    /// Statistics of all the generations evolved so far - or, after
+    /// restoring a snapshot without history, since the last one evolved
+    /// before it got taken.
+    pub fn history(&self) -> &ga::History {
+        &self.history
+    }
+
+    /// Statistics of predators, as in [`Simulation::history()`]; empty if
+    /// there are no predators.
+    pub fn predator_history(&self) -> &ga::History {
+        &self.predator_history
+    }
+
+    /// FNV-1a hash of positions of all the animals and foods, plus fitness
+    /// of the most recently evolved generation; equal fingerprints mean
+    /// that runs went bit-identically, e.g. natively and on wasm.
+    pub fn fingerprint(&self) -> u64 {
+        let stats = self
+            .history
+            .last()
+            .map(|stats| [stats.min_fitness, stats.max_fitness, stats.avg_fitness]);
+
+        let bits = self
+            .world
+            .animals
+            .iter()
+            .flat_map(|animal| {
+                [
+                    animal.position.x,
+                    animal.position.y,
+                    animal.rotation.angle(),
+                ]
+            })
+            .chain(
+                self.world
+                    .foods
+                    .iter()
+                    .flat_map(|food| [food.position.x, food.position.y]),
+            )
+            .chain(stats.into_iter().flatten())
+            .map(f32::to_bits);
+
+        bits.fold(0xcbf29ce484222325, |hash, bits| {
+            (hash ^ bits as u64).wrapping_mul(0x100000001b3)
+        })
+    }
+
+    /// Genome of the fittest animal of the most recently evolved generation.
+    pub fn best_genome(&self) -> Option<Genome> {
+        self.history.last().map(|stats| {
+            Genome::decode(&self.config, stats.best_chromosome.clone())
+                .expect("history got checked on restore")
+        })
+    }
+
+    pub fn step(&mut self, rng: &mut dyn RngCore) -> Option<ga::Statistics> {
+        if self.advance(rng) {
             Some(self.evolve(rng))
         } else {
             None
@@ -68,66 +235,817 @@ impl Simulation {
         }
     }
 
+    /// Steps until the current generation is over and returns its
+    /// statistics, but - unlike [`Simulation::train()`] - doesn't evolve it,
+    /// e.g. to evaluate animals created by [`Simulation::from_genome()`].
+    pub fn evaluate(&mut self, rng: &mut dyn RngCore) -> ga::Statistics {
+        while !self.advance(rng) {}
+
+        self.ga.evaluate(&self.population())
+    }
+
+    /// Performs a single step without evolving; returns whether the current
+    /// generation is over, i.e. it has lasted long enough or all the
+    /// animals have died.
+    pub(crate) fn advance(&mut self, rng: &mut dyn RngCore) -> bool {
+        self.process_collisions(rng);
+        self.process_catches();
+        self.process_brains();
+        self.process_movements();
+        self.process_metabolism();
+
+        self.age += 1;
+
+        self.is_generation_over()
+    }
+
+    pub(crate) fn is_generation_over(&self) -> bool {
+        self.age > self.config.sim_generation_length
+            || self.world.animals.iter().all(|animal| !animal.alive)
+    }
+
     pub fn process_collisions(&mut self, rng: &mut dyn RngCore) {
+        let boundary = self.config.world_boundary;
+        let mut nearby = Vec::new();
+
         for animal in &mut self.world.animals {
-            for food in &mut self.world.foods {
-                let distance = na::distance(&animal.position, &food.position);
+            if !animal.alive {
+                continue;
+            }
+
+            self.world.food_grid.query(
+                animal.position,
+                self.config.food_size,
+                boundary,
+                &mut nearby,
+            );
 
-                if distance <= 0.01 {
-                    animal.satiation += 1;
-                    food.position = rng.random();
+            for &idx in &nearby {
+                let food = &mut self.world.foods[idx];
+                let distance = boundary.distance(animal.position, food.position);
+
+                if distance <= self.config.food_size {
+                    let position = rng.random();
+
+                    self.world.food_grid.relocate(idx, food.position, position);
+
+                    animal.eat(&self.config);
+                    food.position = position;
+                }
+            }
+        }
+    }
+
+    pub fn process_catches(&mut self) {
+        if self.world.predators.is_empty() {
+            return;
+        }
+
+        self.world.index_animals();
+
+        let boundary = self.config.world_boundary;
+        let mut nearby = Vec::new();
+
+        for predator in &mut self.world.predators {
+            self.world.animal_grid.query(
+                predator.position,
+                self.config.predator_catch_size,
+                boundary,
+                &mut nearby,
+            );
+
+            for &idx in &nearby {
+                let animal = &mut self.world.animals[idx];
+
+                if !animal.alive {
+                    continue;
+                }
+
+                let distance = boundary.distance(predator.position, animal.position);
+
+                if distance <= self.config.predator_catch_size {
+                    animal.die();
+                    predator.kills += 1;
                 }
             }
         }
     }
 
     pub fn process_brains(&mut self) {
-        for animal in &mut self.world.animals {
-            let vision =
-                animal
-                    .eye
-                    .process_vision(animal.position, animal.rotation, &self.world.foods);
+        self.brain_inputs.clear();
+
+        // Animals are looked up by birds that see each other and by
+        // predators
+        if self.config.eye_see_animals || !self.world.predators.is_empty() {
+            self.world.index_animals();
+        }
+
+        let world = &self.world;
+        let boundary = self.config.world_boundary;
+
+        let vision = |(idx, animal): (usize, &Animal)| {
+            if !animal.alive {
+                return vec![0.0; animal.eye.inputs()];
+            }
+
+            let mut nearby = Vec::new();
+
+            world.food_grid.query(
+                animal.position,
+                animal.eye.fov_range(),
+                boundary,
+                &mut nearby,
+            );
+
+            let eye = &animal.eye;
+
+            let mut vision = eye.process_vision(
+                animal.position,
+                animal.rotation,
+                nearby.iter().map(|&idx| &world.foods[idx]),
+                boundary,
+            );
+
+            if eye.sees(Channel::Animal) {
+                world
+                    .animal_grid
+                    .query(animal.position, eye.fov_range(), boundary, &mut nearby);
+
+                eye.see(
+                    animal.position,
+                    animal.rotation,
+                    nearby
+                        .iter()
+                        .filter(|&&other| other != idx)
+                        .map(|&other| &world.animals[other]),
+                    boundary,
+                    &mut vision,
+                );
+            }
+
+            if eye.sees(Channel::Predator) {
+                eye.see(
+                    animal.position,
+                    animal.rotation,
+                    &world.predators,
+                    boundary,
+                    &mut vision,
+                );
+            }
+
+            vision
+        };
+
+        #[cfg(feature = "parallel")]
+        let visions: Vec<_> = self
+            .world
+            .animals
+            .par_iter()
+            .enumerate()
+            .map(vision)
+            .collect();
+
+        #[cfg(not(feature = "parallel"))]
+        let visions = self.world.animals.iter().enumerate().map(vision);
+
+        for vision in visions {
+            self.brain_inputs.extend(vision);
+        }
+
+        let responses = self
+            .brains
+            .propagate_into(&self.brain_inputs, &mut self.brain_buffers)
+            .expect("brains got out of sync with animals");
+
+        for (animal, response) in self.world.animals.iter_mut().zip(responses.chunks_exact(2)) {
+            if !animal.alive {
+                continue;
+            }
+
+            steer(
+                &self.config,
+                response,
+                &mut animal.speed,
+                &mut animal.rotation,
+                (self.config.sim_speed_min, self.config.sim_speed_max),
+            );
+        }
+
+        if !self.world.predators.is_empty() {
+            self.process_predator_brains();
+        }
+    }
+
+    fn process_predator_brains(&mut self) {
+        self.predator_brain_inputs.clear();
+
+        let world = &self.world;
+        let mut nearby = Vec::new();
+
+        for predator in &world.predators {
+            world.animal_grid.query(
+                predator.position,
+                predator.eye.fov_range(),
+                self.config.world_boundary,
+                &mut nearby,
+            );
+
+            let vision = predator.eye.process_vision(
+                predator.position,
+                predator.rotation,
+                nearby.iter().map(|&idx| &world.animals[idx]),
+                self.config.world_boundary,
+            );
 
-            let response = animal.brain.nn.propagate(vision);
+            self.predator_brain_inputs.extend(vision);
+        }
 
-            let speed = response[0].clamp(-SPEED_ACCEL, SPEED_ACCEL);
-            let rotation = response[1].clamp(-ROTATION_ACCEL, ROTATION_ACCEL);
+        let responses = self
+            .predator_brains
+            .propagate_into(
+                &self.predator_brain_inputs,
+                &mut self.predator_brain_buffers,
+            )
+            .expect("brains got out of sync with predators");
 
-            animal.speed = (animal.speed * speed).clamp(SPEED_MIN, SPEED_MAX);
-            animal.rotation = na::Rotation2::new(animal.rotation.angle() + rotation);
+        for (predator, response) in self
+            .world
+            .predators
+            .iter_mut()
+            .zip(responses.chunks_exact(2))
+        {
+            steer(
+                &self.config,
+                response,
+                &mut predator.speed,
+                &mut predator.rotation,
+                (
+                    self.config.predator_speed_min,
+                    self.config.predator_speed_max,
+                ),
+            );
         }
     }
 
     pub fn process_movements(&mut self) {
-        for animal in &mut self.world.animals {
+        for animal in self.world.animals.iter_mut().filter(|animal| animal.alive) {
             animal.position += animal.rotation * na::Vector2::new(0.0, animal.speed);
 
-            animal.position.x = na::wrap(animal.position.x, 0.0, 1.0);
-            animal.position.y = na::wrap(animal.position.y, 0.0, 1.0);
+            self.config.world_boundary.confine(&mut animal.position);
+        }
+
+        for predator in &mut self.world.predators {
+            predator.position += predator.rotation * na::Vector2::new(0.0, predator.speed);
+
+            self.config.world_boundary.confine(&mut predator.position);
+        }
+    }
+
+    pub fn process_metabolism(&mut self) {
+        for animal in self.world.animals.iter_mut().filter(|animal| animal.alive) {
+            animal.metabolize(&self.config);
         }
     }
 
     fn evolve(&mut self, rng: &mut dyn RngCore) -> ga::Statistics {
         self.age = 0;
 
-        let current_population: Vec<_> = self
-            .world
+        let population = self.population();
+        let (evolved_population, stats) = self.ga.evolve(rng, &population);
+
+        self.repopulate(rng, evolved_population, &stats);
+
+        stats
+    }
+
+    pub(crate) fn population(&self) -> Vec<AnimalIndividual> {
+        self.world
             .animals
             .iter()
-            .map(AnimalIndividual::from_animal)
-            .collect();
-
-        let (evolved_population, stats) = self.ga.evolve(rng, &current_population);
+            .map(|animal| AnimalIndividual::from_animal(animal, &self.config))
+            .collect()
+    }
 
+    /// Replaces animals with `evolved_population` and starts a new
+    /// generation.
+    pub(crate) fn repopulate(
+        &mut self,
+        rng: &mut dyn RngCore,
+        evolved_population: Vec<AnimalIndividual>,
+        stats: &ga::Statistics,
+    ) {
         self.world.animals = evolved_population
             .into_iter()
-            .map(|individual| individual.into_animal(rng))
+            .map(|individual| individual.into_animal(&self.config, rng))
             .collect();
 
+        self.brains = Brain::batch(self.world.animals.iter().map(|animal| &animal.brain));
+
         for food in &mut self.world.foods {
             food.position = rng.random();
         }
 
-        stats
+        self.world.index_foods();
+
+        if !self.world.predators.is_empty() {
+            self.evolve_predators(rng);
+        }
+
+        self.history.push(stats.clone());
+    }
+
+    fn evolve_predators(&mut self, rng: &mut dyn RngCore) {
+        let population: Vec<_> = self
+            .world
+            .predators
+            .iter()
+            .map(AnimalIndividual::from_predator)
+            .collect();
+
+        let (evolved_population, stats) = self.predator_ga.evolve(rng, &population);
+
+        self.world.predators = evolved_population
+            .into_iter()
+            .map(|individual| individual.into_predator(&self.config, rng))
+            .collect();
+
+        self.predator_brains =
+            Brain::batch(self.world.predators.iter().map(|predator| &predator.brain));
+
+        self.predator_history.push(stats);
+    }
+}
+
+/// Applies brain's `response` - change of speed and rotation - to a creature
+/// moving within `speed_range`.
+fn steer(
+    config: &Config,
+    response: &[f32],
+    speed: &mut f32,
+    rotation: &mut na::Rotation2<f32>,
+    speed_range: (f32, f32),
+) {
+    let speed_change = response[0].clamp(-config.sim_speed_accel, config.sim_speed_accel);
+    let rotation_change = response[1].clamp(-config.sim_rotation_accel, config.sim_rotation_accel);
+
+    *speed = (*speed * speed_change).clamp(speed_range.0, speed_range.1);
+    *rotation = na::Rotation2::new(rotation.angle() + rotation_change);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+    use test_case::test_case;
+
+    fn config() -> Config {
+        Config {
+            sim_generation_length: 50,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn from_seed_is_deterministic() {
+        let (mut sim_a, mut rng_a) = Simulation::from_seed(config(), 42);
+        let (mut sim_b, mut rng_b) = Simulation::from_seed(config(), 42);
+
+        assert_eq!(sim_a.world(), sim_b.world());
+
+        for _ in 0..3 {
+            assert_eq!(sim_a.train(&mut rng_a), sim_b.train(&mut rng_b));
+            assert_eq!(sim_a.world(), sim_b.world());
+        }
+    }
+
+    #[test]
+    fn different_seeds_diverge() {
+        let (sim_a, _) = Simulation::from_seed(config(), 1);
+        let (sim_b, _) = Simulation::from_seed(config(), 2);
+
+        assert_ne!(sim_a.world(), sim_b.world());
+    }
+
+    #[test]
+    fn starving_animals_end_generation() {
+        let config = Config {
+            energy_cost_base: 0.25,
+            sim_fitness: Fitness::Survival,
+            sim_fitness_energy_weight: 0.0,
+            world_foods: 0,
+            ..config()
+        };
+
+        let (mut sim, mut rng) = Simulation::from_seed(config, 42);
+
+        // Energy goes 1.0 -> 0.75 -> 0.5 -> 0.25 -> 0.0
+        for _ in 0..3 {
+            assert!(sim.step(&mut rng).is_none());
+        }
+
+        let positions: Vec<_> = sim
+            .world()
+            .animals()
+            .iter()
+            .map(|animal| animal.position())
+            .collect();
+
+        assert!(sim.advance(&mut rng));
+
+        for (animal, position) in sim.world().animals().iter().zip(positions) {
+            assert!(!animal.is_alive());
+            assert_eq!(animal.lifetime(), 3);
+            assert_eq!(animal.energy(), 0.0);
+
+            // They still made the step they starved on
+            assert_ne!(animal.position(), position);
+        }
+
+        let stats = sim.evolve(&mut rng);
+
+        assert_eq!(stats.min_fitness, 3.0);
+        assert_eq!(stats.max_fitness, 3.0);
+    }
+
+    #[test]
+    fn movement_costs_energy() {
+        let config = Config {
+            energy_cost_speed: 10.0,
+            world_foods: 0,
+            ..config()
+        };
+
+        let (mut sim, mut rng) = Simulation::from_seed(config, 42);
+
+        sim.step(&mut rng);
+
+        for animal in sim.world().animals() {
+            assert_relative_eq!(animal.energy(), 1.0 - 10.0 * animal.speed());
+        }
+    }
+
+    #[test]
+    fn eating_restores_energy() {
+        let config = Config {
+            energy_cost_base: 0.1,
+            world_animals: 1,
+            world_foods: 1,
+            ..config()
+        };
+
+        let (mut sim, mut rng) = Simulation::from_seed(config, 42);
+
+        sim.world.foods[0].position = sim.world.animals[0].position;
+        sim.world.index_foods();
+        sim.process_collisions(&mut rng);
+        sim.process_metabolism();
+
+        let animal = &sim.world().animals()[0];
+
+        assert_eq!(animal.satiation(), 1);
+        assert_relative_eq!(animal.energy(), 1.0 + 0.25 - 0.1);
+        assert_relative_eq!(animal.fitness(sim.config()), 1.0);
+    }
+
+    #[test]
+    fn predators_catch_animals() {
+        let config = Config {
+            world_animals: 2,
+            world_predators: 1,
+            ..config()
+        };
+
+        let (mut sim, _) = Simulation::from_seed(config, 42);
+
+        sim.world.predators[0].position = sim.world.animals[1].position;
+        sim.process_catches();
+
+        assert!(sim.world().animals()[0].is_alive());
+        assert!(!sim.world().animals()[1].is_alive());
+        assert_eq!(sim.world().predators()[0].kills(), 1);
+
+        // Dead animals can't be caught again
+        sim.process_catches();
+
+        assert_eq!(sim.world().predators()[0].kills(), 1);
+    }
+
+    #[test]
+    fn predators_coevolve() {
+        let config = Config {
+            world_predators: 4,
+            ..config()
+        };
+
+        let (mut sim_a, mut rng_a) = Simulation::from_seed(config.clone(), 42);
+        let (mut sim_b, mut rng_b) = Simulation::from_seed(config, 42);
+
+        let animal = &sim_a.world().animals()[0];
+
+        assert_eq!(animal.eye.channels(), [Channel::Food, Channel::Predator]);
+        assert_eq!(
+            animal.brain.nn.topology()[0].neurons,
+            2 * animal.eye.cells()
+        );
+
+        for _ in 0..2 {
+            assert_eq!(sim_a.train(&mut rng_a), sim_b.train(&mut rng_b));
+            assert_eq!(sim_a.world(), sim_b.world());
+        }
+
+        assert_eq!(sim_a.predator_history().len(), 2);
+        assert_eq!(sim_a.predator_history(), sim_b.predator_history());
+        assert_eq!(sim_a.world().predators().len(), 4);
+    }
+
+    #[test]
+    fn sees_animals_and_walls() {
+        let config = Config {
+            eye_see_animals: true,
+            eye_see_walls: true,
+            world_boundary: Boundary::Walled,
+            ..config()
+        };
+
+        let (mut sim, mut rng) = Simulation::from_seed(config, 42);
+        let animal = &sim.world().animals()[0];
+
+        assert_eq!(
+            animal.eye.channels(),
+            [Channel::Food, Channel::Animal, Channel::Wall]
+        );
+
+        assert_eq!(
+            animal.brain.nn.topology()[0].neurons,
+            3 * animal.eye.cells()
+        );
+
+        sim.step(&mut rng);
+
+        // Some animal must be seeing another one
+        assert!(sim.brain_inputs.chunks_exact(3).any(|cell| cell[1] > 0.0));
+
+        sim.train(&mut rng);
+    }
+
+    #[test_case(Boundary::Toroidal)]
+    #[test_case(Boundary::Walled)]
+    fn indexed_animal_vision_matches_full_scan(boundary: Boundary) {
+        let config = Config {
+            eye_see_animals: true,
+            eye_fov_range: 0.1,
+            world_animals: 200,
+            world_boundary: boundary,
+            ..config()
+        };
+
+        let (mut sim, mut rng) = Simulation::from_seed(config, 42);
+
+        for _ in 0..10 {
+            sim.step(&mut rng);
+        }
+
+        // Brains steer animals right away, so let's look before they do
+        let world = sim.world().clone();
+
+        sim.process_brains();
+
+        let inputs = world.animals[0].eye.inputs();
+
+        for (idx, animal) in world.animals.iter().enumerate() {
+            let eye = &animal.eye;
+
+            let mut vision =
+                eye.process_vision(animal.position, animal.rotation, &world.foods, boundary);
+
+            eye.see(
+                animal.position,
+                animal.rotation,
+                world
+                    .animals
+                    .iter()
+                    .enumerate()
+                    .filter(|(other, _)| *other != idx)
+                    .map(|(_, other)| other),
+                boundary,
+                &mut vision,
+            );
+
+            assert_eq!(vision, sim.brain_inputs[idx * inputs..][..inputs]);
+        }
+
+        assert!(sim.brain_inputs.chunks_exact(2).any(|cell| cell[1] > 0.0));
+    }
+
+    #[test_case(Boundary::Toroidal)]
+    #[test_case(Boundary::Walled)]
+    fn indexed_predators_match_full_scan(boundary: Boundary) {
+        let config = Config {
+            predator_catch_size: 0.05,
+            predator_eye_fov_range: 0.1,
+            world_animals: 200,
+            world_predators: 20,
+            world_boundary: boundary,
+            ..config()
+        };
+
+        // Fresh world, so that there's plenty of birds left to catch
+        let (mut sim, _) = Simulation::from_seed(config.clone(), 42);
+
+        let before = sim.world().clone();
+        let mut world = before.clone();
+
+        for predator in &mut world.predators {
+            for animal in world.animals.iter_mut().filter(|animal| animal.alive) {
+                if boundary.distance(predator.position, animal.position)
+                    <= config.predator_catch_size
+                {
+                    animal.die();
+                    predator.kills += 1;
+                }
+            }
+        }
+
+        sim.process_catches();
+
+        assert_eq!(sim.world(), &world);
+        assert_ne!(world, before);
+
+        // Brains steer predators right away, so let's look before they do
+        let world = sim.world().clone();
+
+        sim.process_brains();
+
+        let inputs = world.predators[0].eye.inputs();
+
+        for (idx, predator) in world.predators.iter().enumerate() {
+            let vision = predator.eye.process_vision(
+                predator.position,
+                predator.rotation,
+                &world.animals,
+                boundary,
+            );
+
+            assert_eq!(vision, sim.predator_brain_inputs[idx * inputs..][..inputs]);
+        }
+
+        assert!(sim.predator_brain_inputs.iter().any(|&input| input > 0.0));
+    }
+
+    #[test]
+    fn eyes_coevolve() {
+        let config = Config {
+            eye_evolve: true,
+            ga_mut_chance: 0.5,
+            ..config()
+        };
+
+        let (mut sim, mut rng) = Simulation::from_seed(config.clone(), 42);
+
+        let weights = sim.world().animals()[0].brain.as_chromosome().len();
+
+        for _ in 0..3 {
+            let stats = sim.train(&mut rng);
+
+            assert_eq!(stats.best_chromosome.len(), weights + Eye::GENES);
+        }
+
+        let eyes: Vec<_> = sim.world().animals().iter().map(Animal::eye).collect();
+
+        assert!(eyes.iter().all(|eye| {
+            (config.eye_fov_range_min..=config.eye_fov_range_max).contains(&eye.fov_range())
+                && (config.eye_fov_angle_min..=config.eye_fov_angle_max).contains(&eye.fov_angle())
+                && eye.cells() == config.eye_cells
+        }));
+
+        assert!(
+            eyes.iter()
+                .any(|eye| eye.fov_range() != config.eye_fov_range),
+            "eyes should've mutated"
+        );
+
+        // Brains of evolved eyes have the same shape
+        let genome = sim.best_genome().unwrap();
+
+        assert_eq!(genome.brain().as_chromosome().len(), weights);
+
+        // Exported genome keeps the evolved eye
+        let best: Vec<_> = sim
+            .history()
+            .last()
+            .unwrap()
+            .best_chromosome
+            .iter()
+            .copied()
+            .collect();
+
+        assert_eq!(
+            *genome.eye(),
+            Eye::from_genes(&config, best[weights..].try_into().unwrap())
+        );
+
+        let genome = Genome::from_json(&config, &genome.to_json().unwrap()).unwrap();
+        let sim = Simulation::from_genome(config.clone(), &genome, &mut rng);
+
+        assert!(
+            sim.world()
+                .animals()
+                .iter()
+                .all(|animal| animal.eye == genome.eye && animal.brain == genome.brain)
+        );
+    }
+
+    #[test]
+    fn evaluate_doesnt_evolve() {
+        let (mut sim, mut rng) = Simulation::from_seed(config(), 42);
+        let (mut trained_sim, mut trained_rng) = Simulation::from_seed(config(), 42);
+
+        let brains: Vec<_> = sim
+            .world()
+            .animals()
+            .iter()
+            .map(|a| a.brain.clone())
+            .collect();
+
+        let stats = sim.evaluate(&mut rng);
+        let expected = trained_sim.train(&mut trained_rng);
+
+        assert_eq!(stats.generation, expected.generation);
+        assert_eq!(stats.max_fitness, expected.max_fitness);
+        assert_eq!(stats.avg_fitness, expected.avg_fitness);
+        assert_eq!(stats.best_chromosome, expected.best_chromosome);
+        assert_eq!((stats.survivors, stats.offspring), (0, 0));
+
+        assert_eq!(sim.generation(), 0);
+        assert!(sim.history().is_empty());
+
+        assert!(
+            sim.world()
+                .animals()
+                .iter()
+                .zip(&brains)
+                .all(|(animal, brain)| animal.brain == *brain)
+        );
+    }
+
+    #[test]
+    fn best_genome_roundtrip() {
+        let (mut sim, mut rng) = Simulation::from_seed(config(), 42);
+
+        assert!(sim.best_genome().is_none());
+
+        let stats = sim.train(&mut rng);
+        let genome = sim.best_genome().unwrap();
+
+        assert_eq!(genome.brain().as_chromosome(), stats.best_chromosome);
+        assert_eq!(*genome.eye(), Eye::new(sim.config()));
+
+        let json = genome.to_json().unwrap();
+
+        assert_eq!(Genome::from_json(sim.config(), &json).unwrap(), genome);
+
+        let other_config = Config {
+            eye_cells: 3,
+            ..config()
+        };
+
+        assert!(matches!(
+            Genome::from_json(&other_config, &json),
+            Err(Error::IncompatibleEye { .. })
+        ));
+
+        let json = genome.brain().to_json().unwrap();
+
+        assert_eq!(Brain::from_json(sim.config(), &json).unwrap(), genome.brain);
+
+        assert!(matches!(
+            Brain::from_json(&other_config, &json),
+            Err(Error::IncompatibleBrain { .. })
+        ));
+
+        let sim = Simulation::from_genome(config(), &genome, &mut rng);
+
+        assert!(
+            sim.world()
+                .animals()
+                .iter()
+                .all(|animal| animal.brain == genome.brain)
+        );
+    }
+
+    /// Must match the value checked by `simulation-wasm`'s
+    /// `tests/fingerprint.rs`, which CI runs on wasm32 - that's what
+    /// guarantees native and wasm runs stay bit-identical.
+    #[test]
+    fn from_seed_fingerprint() {
+        let (mut sim, mut rng) = Simulation::from_seed(config(), 42);
+
+        sim.train(&mut rng);
+
+        for _ in 0..50 {
+            sim.step(&mut rng);
+        }
+
+        assert_eq!(sim.fingerprint(), 16446425090840397952);
     }
 }
@@ -0,0 +1,84 @@
+use crate::*;
+
+/// Hunts birds - sees them through its own eye and, when close enough,
+/// catches (i.e. kills) them.
+///
+/// Predators are evolved by a genetic algorithm of their own, which scores
+/// them by the number of birds caught.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Predator {
+    pub(crate) position: na::Point2<f32>,
+    pub(crate) rotation: na::Rotation2<f32>,
+    pub(crate) speed: f32,
+    pub(crate) eye: Eye,
+    pub(crate) brain: Brain,
+
+    /// Number of birds caught during the current generation
+    pub(crate) kills: usize,
+}
+
+impl Predator {
+    fn new(eye: Eye, brain: Brain, rng: &mut dyn RngCore) -> Self {
+        Self {
+            position: rng.random(),
+            rotation: rng.random(),
+            speed: 0.002,
+            eye,
+            brain,
+            kills: 0,
+        }
+    }
+
+    pub(crate) fn from_chromosome(
+        config: &Config,
+        chromosome: ga::Chromosome,
+        rng: &mut dyn RngCore,
+    ) -> Self {
+        let eye = Eye::predator(config);
+        let brain = Brain::from_chromosome(chromosome, &eye);
+
+        Self::new(eye, brain, rng)
+    }
+
+    pub fn random(config: &Config, rng: &mut dyn RngCore) -> Self {
+        let eye = Eye::predator(config);
+        let brain = Brain::random(rng, &eye);
+
+        Self::new(eye, brain, rng)
+    }
+
+    /// Checks that `chromosome` describes a predator of `config`.
+    pub(crate) fn check_chromosome(config: &Config, chromosome: &ga::Chromosome) -> Result<()> {
+        let expected = Brain::weight_count(&Eye::predator(config));
+
+        if chromosome.len() != expected {
+            return Err(Error::IncompatibleChromosome {
+                got: chromosome.len(),
+                expected,
+            });
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn as_chromosome(&self) -> ga::Chromosome {
+        self.brain.as_chromosome()
+    }
+
+    pub fn position(&self) -> na::Point2<f32> {
+        self.position
+    }
+
+    pub fn rotation(&self) -> na::Rotation2<f32> {
+        self.rotation
+    }
+
+    pub fn speed(&self) -> f32 {
+        self.speed
+    }
+
+    /// Number of birds caught during the current generation.
+    pub fn kills(&self) -> usize {
+        self.kills
+    }
+}
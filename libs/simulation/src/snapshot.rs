@@ -0,0 +1,389 @@
+use crate::*;
+
+/// Version of the snapshot format; bumped whenever the layout of any of the
+/// serialized types changes, so that stale snapshots are rejected up front
+/// instead of being misread.
+pub const SNAPSHOT_VERSION: u32 = 13;
+
+/// Complete state of a [`Simulation`] together with the generator driving
+/// it, so that a resumed run continues exactly where the original one
+/// stopped.
+///
+/// The genetic algorithm itself isn't stored - it's rebuilt from `config`,
+/// with the state it carries between generations restored on top.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    version: u32,
+    config: Config,
+    world: World,
+    age: usize,
+    generation: usize,
+    predator_generation: usize,
+
+    /// Either all of the statistics or just the most recent ones; see
+    /// [`Simulation::snapshot()`]
+    history: ga::History,
+    species: Vec<ga::Species>,
+    predator_history: ga::History,
+    rng: ChaCha8Rng,
+}
+
+#[derive(Deserialize)]
+struct SnapshotHeader {
+    version: u32,
+}
+
+impl Snapshot {
+    pub(crate) fn new(sim: &Simulation, rng: &ChaCha8Rng, with_history: bool) -> Self {
+        let history = |history: &ga::History| {
+            if with_history {
+                history.clone()
+            } else {
+                let mut latest = ga::History::default();
+
+                if let Some(stats) = history.last() {
+                    latest.push(stats.clone());
+                }
+
+                latest
+            }
+        };
+
+        Self {
+            version: SNAPSHOT_VERSION,
+            config: sim.config.clone(),
+            world: sim.world.clone(),
+            age: sim.age,
+            generation: sim.ga.generation(),
+            predator_generation: sim.predator_ga.generation(),
+            history: history(&sim.history),
+            species: sim
+                .ga
+                .speciation()
+                .map(|speciation| speciation.species().to_vec())
+                .unwrap_or_default(),
+            predator_history: history(&sim.predator_history),
+            rng: rng.clone(),
+        }
+    }
+
+    /// Rebuilds the simulation, after checking that the config is valid and
+    /// that everything else matches it - snapshots can come from anywhere,
+    /// after all.
+    pub(crate) fn restore(self) -> Result<(Simulation, ChaCha8Rng)> {
+        self.config.validate()?;
+        self.world.check(&self.config)?;
+
+        for stats in self.history.iter() {
+            Animal::check_chromosome(&self.config, &stats.best_chromosome)?;
+        }
+
+        for species in &self.species {
+            Animal::check_chromosome(&self.config, species.representative())?;
+        }
+
+        for stats in self.predator_history.iter() {
+            Predator::check_chromosome(&self.config, &stats.best_chromosome)?;
+        }
+
+        let mut sim = Simulation::from_world(self.config, self.world);
+
+        sim.age = self.age;
+        sim.ga.set_generation(self.generation);
+        sim.history = self.history;
+        sim.predator_ga.set_generation(self.predator_generation);
+        sim.predator_history = self.predator_history;
+
+        if let Some(speciation) = sim.ga.speciation_mut() {
+            speciation.set_species(self.species);
+        }
+
+        Ok((sim, self.rng))
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self> {
+        let header: SnapshotHeader = serde_json::from_str(json)?;
+
+        Self::check_version(header.version)?;
+
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Serializes snapshot into a compact, non-self-describing binary form
+    /// (postcard).
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        Ok(postcard::to_allocvec(self)?)
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        // `version` is the first field, so it can be read before we commit
+        // to decoding the rest of the buffer
+        let (version, _) = postcard::take_from_bytes::<u32>(bytes)?;
+
+        Self::check_version(version)?;
+
+        Ok(postcard::from_bytes(bytes)?)
+    }
+
+    fn check_version(version: u32) -> Result<()> {
+        if version != SNAPSHOT_VERSION {
+            return Err(Error::UnsupportedSnapshotVersion {
+                got: version,
+                expected: SNAPSHOT_VERSION,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn simulation() -> (Simulation, ChaCha8Rng) {
+        let config = Config {
+            sim_generation_length: 50,
+            ga_species_threshold: 0.3,
+            world_predators: 3,
+            ..Default::default()
+        };
+
+        let (mut sim, mut rng) = Simulation::from_seed(config, 42);
+
+        sim.train(&mut rng);
+
+        for _ in 0..10 {
+            sim.step(&mut rng);
+        }
+
+        (sim, rng)
+    }
+
+    fn assert_resumes_identically(restored: Snapshot) {
+        let (mut sim_a, mut rng_a) = simulation();
+        let (mut sim_b, mut rng_b) = restored.restore().unwrap();
+
+        assert_eq!(sim_a.world(), sim_b.world());
+        assert_eq!(sim_a.age, sim_b.age);
+        assert_eq!(sim_a.config(), sim_b.config());
+
+        assert_eq!(sim_a.history(), sim_b.history());
+
+        assert_eq!(sim_a.train(&mut rng_a), sim_b.train(&mut rng_b));
+        assert_eq!(sim_a.world(), sim_b.world());
+        assert_eq!(sim_a.history(), sim_b.history());
+        assert_eq!(sim_a.predator_history(), sim_b.predator_history());
+        assert_eq!(sim_b.history().last().unwrap().generation, 1);
+        assert_eq!(sim_b.predator_history().last().unwrap().generation, 1);
+        assert!(!sim_b.history().last().unwrap().species_sizes.is_empty());
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let (sim, rng) = simulation();
+        let json = sim.snapshot(&rng).to_json().unwrap();
+
+        assert_resumes_identically(Snapshot::from_json(&json).unwrap());
+    }
+
+    #[test]
+    fn bytes_roundtrip() {
+        let (sim, rng) = simulation();
+        let bytes = sim.snapshot(&rng).to_bytes().unwrap();
+
+        assert_resumes_identically(Snapshot::from_bytes(&bytes).unwrap());
+    }
+
+    #[test]
+    fn keeps_only_latest_statistics() {
+        let (mut sim, mut rng) = simulation();
+
+        for _ in 0..2 {
+            sim.train(&mut rng);
+        }
+
+        assert_eq!(sim.snapshot_with_history(&rng).history, *sim.history());
+
+        let (mut restored, mut restored_rng) = sim.snapshot(&rng).restore().unwrap();
+
+        assert_eq!(restored.generation(), 3);
+        assert_eq!(restored.history().len(), 1);
+        assert_eq!(restored.history().last(), sim.history().last());
+        assert_eq!(restored.best_genome(), sim.best_genome());
+
+        let stats = restored.train(&mut restored_rng);
+
+        assert_eq!(stats, sim.train(&mut rng));
+        assert_eq!(stats.generation, 3);
+        assert_eq!(restored.predator_history().last().unwrap().generation, 3);
+    }
+
+    #[test]
+    fn rejects_unsupported_version() {
+        let (sim, rng) = simulation();
+
+        let mut snapshot = sim.snapshot(&rng);
+        snapshot.version = SNAPSHOT_VERSION + 1;
+
+        let json = snapshot.to_json().unwrap();
+        let bytes = snapshot.to_bytes().unwrap();
+
+        assert!(matches!(
+            Snapshot::from_json(&json),
+            Err(Error::UnsupportedSnapshotVersion { .. })
+        ));
+
+        assert!(matches!(
+            Snapshot::from_bytes(&bytes),
+            Err(Error::UnsupportedSnapshotVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_brains_of_wrong_shape() {
+        let (sim, rng) = simulation();
+
+        let mut snapshot = sim.snapshot(&rng);
+
+        let other_config = Config {
+            eye_cells: 3,
+            ..Default::default()
+        };
+
+        snapshot.world.animals[0].brain = Brain::random(&mut rng.clone(), &Eye::new(&other_config));
+
+        assert!(matches!(
+            snapshot.restore(),
+            Err(Error::IncompatibleBrain { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_eyes() {
+        let (sim, rng) = simulation();
+
+        let mut snapshot = sim.snapshot(&rng);
+        snapshot.world.predators[0].eye = Eye::new(&snapshot.config);
+
+        assert!(matches!(
+            snapshot.clone().restore(),
+            Err(Error::IncompatibleEye { .. })
+        ));
+
+        snapshot.config.eye_fov_range = f32::NAN;
+
+        assert!(matches!(snapshot.restore(), Err(Error::InvalidEye { .. })));
+    }
+
+    #[test]
+    fn rejects_wrong_population() {
+        let (sim, rng) = simulation();
+
+        let mut snapshot = sim.snapshot(&rng);
+        snapshot.world.animals.pop();
+
+        assert!(matches!(
+            snapshot.restore(),
+            Err(Error::IncompatiblePopulation {
+                what: "animals",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_history_of_wrong_shape() {
+        let (sim, rng) = simulation();
+
+        // Eye genes would be missing from the best chromosomes
+        let mut snapshot = sim.snapshot(&rng);
+        snapshot.config.eye_evolve = true;
+
+        assert!(matches!(
+            snapshot.restore(),
+            Err(Error::IncompatibleChromosome { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_mutation_chance() {
+        let (sim, rng) = simulation();
+
+        let mut snapshot = sim.snapshot(&rng);
+        snapshot.config.ga_mut_chance = 2.0;
+
+        assert!(matches!(
+            snapshot.restore(),
+            Err(Error::InvalidConfig {
+                field: "ga_mut_chance",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_invalid_species_boost() {
+        let (sim, rng) = simulation();
+
+        let mut snapshot = sim.snapshot(&rng);
+        snapshot.config.ga_species_boost = 0.5;
+
+        assert!(matches!(
+            snapshot.restore(),
+            Err(Error::InvalidConfig {
+                field: "ga_species_boost",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn rejects_species_of_wrong_shape() {
+        let (sim, rng) = simulation();
+
+        let mut snapshot = sim.snapshot(&rng);
+        assert!(!snapshot.species.is_empty());
+
+        let mut json: serde_json::Value =
+            serde_json::from_str(&snapshot.to_json().unwrap()).unwrap();
+        json["species"][0]["representative"]
+            .as_array_mut()
+            .unwrap()
+            .pop();
+        snapshot = serde_json::from_value(json).unwrap();
+
+        assert!(matches!(
+            snapshot.restore(),
+            Err(Error::IncompatibleChromosome { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_predator_history_of_wrong_shape() {
+        let (sim, rng) = simulation();
+
+        // Predators' brains would get more inputs than their chromosomes
+        // have weights for
+        let mut snapshot = sim.snapshot(&rng);
+        snapshot.config.predator_eye_cells += 1;
+
+        for predator in &mut snapshot.world.predators {
+            predator.eye = Eye::predator(&snapshot.config);
+            predator.brain = Brain::random(&mut rng.clone(), &predator.eye);
+        }
+
+        assert!(matches!(
+            snapshot.restore(),
+            Err(Error::IncompatibleChromosome { .. })
+        ));
+    }
+}
@@ -0,0 +1,62 @@
+use crate::*;
+
+/// Kind of thing an eye can see; each kind is seen through a separate set of
+/// values, so that brains can tell e.g. food and predators apart.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Channel {
+    Food,
+    Animal,
+    Predator,
+
+    /// Edges of a walled world (see [`Boundary::Walled`]); they aren't
+    /// entities, so eyes see them on their own
+    Wall,
+}
+
+/// Entity that can be seen by an [`Eye`].
+pub trait Visible {
+    fn position(&self) -> na::Point2<f32>;
+
+    fn channel(&self) -> Channel;
+
+    fn is_visible(&self) -> bool {
+        true
+    }
+}
+
+impl Visible for Food {
+    fn position(&self) -> na::Point2<f32> {
+        self.position
+    }
+
+    fn channel(&self) -> Channel {
+        Channel::Food
+    }
+}
+
+impl Visible for Animal {
+    fn position(&self) -> na::Point2<f32> {
+        self.position
+    }
+
+    fn channel(&self) -> Channel {
+        Channel::Animal
+    }
+
+    /// Dead animals lie around until the end of generation, but are of no
+    /// interest to anybody.
+    fn is_visible(&self) -> bool {
+        self.alive
+    }
+}
+
+impl Visible for Predator {
+    fn position(&self) -> na::Point2<f32> {
+        self.position
+    }
+
+    fn channel(&self) -> Channel {
+        Channel::Predator
+    }
+}
@@ -1,18 +1,58 @@
 use crate::*;
 
-#[derive(Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct World {
     pub(crate) animals: Vec<Animal>,
     pub(crate) foods: Vec<Food>,
+    pub(crate) predators: Vec<Predator>,
+
+    /// Index of `foods`, kept in sync by `Simulation`; see
+    /// [`World::index_foods()`]
+    #[serde(skip)]
+    pub(crate) food_grid: Grid,
+
+    /// Index of `animals`, rebuilt on each step by `Simulation` when birds
+    /// see each other or there are predators hunting them; see
+    /// [`World::index_animals()`]
+    #[serde(skip)]
+    pub(crate) animal_grid: Grid,
 }
 
 impl World {
-    pub fn random(rng: &mut dyn RngCore) -> Self {
-        let animals = (0..40).map(|_| Animal::random(rng)).collect();
+    pub fn random(config: &Config, rng: &mut dyn RngCore) -> Self {
+        let animals = (0..config.world_animals)
+            .map(|_| Animal::random(config, rng))
+            .collect();
 
-        let foods = (0..60).map(|_| Food::random(rng)).collect();
+        let foods = (0..config.world_foods).map(|_| Food::random(rng)).collect();
+        let predators = Self::random_predators(config, rng);
 
-        Self { animals, foods }
+        Self {
+            animals,
+            foods,
+            predators,
+            food_grid: Grid::default(),
+            animal_grid: Grid::default(),
+        }
+    }
+
+    /// Creates a world where all the animals share the same `genome`, e.g.
+    /// to evaluate a trained one.
+    pub fn from_genome(config: &Config, genome: &Genome, rng: &mut dyn RngCore) -> Self {
+        let animals = (0..config.world_animals)
+            .map(|_| Animal::from_genome(config, genome.clone(), rng))
+            .collect();
+
+        let foods = (0..config.world_foods).map(|_| Food::random(rng)).collect();
+        let predators = Self::random_predators(config, rng);
+
+        Self {
+            animals,
+            foods,
+            predators,
+            food_grid: Grid::default(),
+            animal_grid: Grid::default(),
+        }
     }
 
     pub fn animals(&self) -> &[Animal] {
@@ -22,4 +62,73 @@ impl World {
     pub fn foods(&self) -> &[Food] {
         &self.foods
     }
+
+    pub fn predators(&self) -> &[Predator] {
+        &self.predators
+    }
+
+    fn random_predators(config: &Config, rng: &mut dyn RngCore) -> Vec<Predator> {
+        (0..config.world_predators)
+            .map(|_| Predator::random(config, rng))
+            .collect()
+    }
+
+    /// Checks that this world (e.g. a deserialized one) matches `config`, so
+    /// that simulating it won't panic.
+    pub(crate) fn check(&self, config: &Config) -> Result<()> {
+        let check_len = |what, got, expected| {
+            if got == expected {
+                Ok(())
+            } else {
+                Err(Error::IncompatiblePopulation {
+                    what,
+                    got,
+                    expected,
+                })
+            }
+        };
+
+        check_len("animals", self.animals.len(), config.world_animals)?;
+        check_len("foods", self.foods.len(), config.world_foods)?;
+        check_len("predators", self.predators.len(), config.world_predators)?;
+
+        let eye = Eye::new(config);
+
+        for animal in &self.animals {
+            animal.eye.check(&eye)?;
+            animal.brain.check(&eye)?;
+        }
+
+        let eye = Eye::predator(config);
+
+        for predator in &self.predators {
+            predator.eye.check(&eye)?;
+            predator.brain.check(&eye)?;
+        }
+
+        Ok(())
+    }
+
+    /// Rebuilds the index of foods; needed after foods got moved or the
+    /// world got deserialized.
+    pub(crate) fn index_foods(&mut self) {
+        self.food_grid
+            .rebuild(self.foods.iter().map(|food| food.position));
+    }
+
+    /// Rebuilds the index of animals; since they move all the time, it's
+    /// rebuilt from scratch rather than kept in sync.
+    pub(crate) fn index_animals(&mut self) {
+        self.animal_grid
+            .rebuild(self.animals.iter().map(|animal| animal.position));
+    }
+}
+
+/// Ignores the indices, since they're derived from foods and animals.
+impl PartialEq for World {
+    fn eq(&self, other: &Self) -> bool {
+        self.animals == other.animals
+            && self.foods == other.foods
+            && self.predators == other.predators
+    }
 }